@@ -0,0 +1,103 @@
+use std::fmt::{Display, Formatter};
+
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, TypedBuilder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArchiveConfiguration {
+    #[builder(setter(into))]
+    url: String,
+
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[builder(default, setter(into, strip_option))]
+    sha256: Option<String>,
+}
+
+impl ArchiveConfiguration {
+    /// Returns the HTTP(S) URL of the archive, or a path that is relative to the project
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the expected SHA-256 checksum of the archive as a hexadecimal string, if any
+    pub fn sha256(&self) -> Option<&str> {
+        self.sha256.as_deref()
+    }
+}
+
+impl Display for ArchiveConfiguration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "archive: {}", self.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize() {
+        let yaml = indoc!(
+            r#"
+            ---
+            url: https://artifacts.example.com/templates-1.0.0.tar.gz
+            "#
+        );
+
+        let configuration = serde_yaml::from_str::<ArchiveConfiguration>(yaml).unwrap();
+
+        assert_eq!(
+            "https://artifacts.example.com/templates-1.0.0.tar.gz",
+            configuration.url()
+        );
+        assert_eq!(None, configuration.sha256());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_checksum() {
+        let yaml = indoc!(
+            r#"
+            ---
+            url: templates.zip
+            sha256: 4d2b6f6d3c0b4f2c0e0a6b0a8d0c6f7b1e5d2a3c9b8e7f6a5d4c3b2a1f0e9d8c
+            "#
+        );
+
+        let configuration = serde_yaml::from_str::<ArchiveConfiguration>(yaml).unwrap();
+
+        assert_eq!(
+            Some("4d2b6f6d3c0b4f2c0e0a6b0a8d0c6f7b1e5d2a3c9b8e7f6a5d4c3b2a1f0e9d8c"),
+            configuration.sha256()
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        let configuration = ArchiveConfiguration::builder().url("templates.zip").build();
+
+        assert_eq!("archive: templates.zip", configuration.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<ArchiveConfiguration>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<ArchiveConfiguration>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<ArchiveConfiguration>();
+    }
+}
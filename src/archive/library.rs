@@ -0,0 +1,536 @@
+use std::fmt::{Display, Formatter};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use flate2::read::GzDecoder;
+use tokio::sync::OnceCell;
+use url::Url;
+
+use crate::archive::ArchiveConfiguration;
+use crate::cache::{hash, Cache};
+use crate::error::Error;
+use crate::fragment::{Fragment, FragmentLibrary};
+use crate::local::LocalLibrary;
+use crate::Project;
+
+/// A library of fragments in a `.tar.gz` or `.zip` archive
+///
+/// The archive is downloaded from an HTTP(S) URL or read from a local file, and then extracted into
+/// the cache. If the archive contains only a single directory, e.g. `templates-1.0.0/`, the
+/// fragments are read from that directory.
+///
+/// An archive with a configured checksum is only downloaded if it has not been extracted before,
+/// since its contents cannot change. Other archives are downloaded on every run, and only read
+/// from the cache in offline mode.
+#[derive(Clone, Debug)]
+pub struct ArchiveLibrary {
+    config: ArchiveConfiguration,
+    location: Location,
+    cache: Cache,
+    offline: bool,
+    contents: OnceCell<LocalLibrary>,
+}
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+enum Location {
+    Url(Url),
+    Path(PathBuf),
+}
+
+impl ArchiveLibrary {
+    pub fn new(project: &Project, config: ArchiveConfiguration, cache: Cache) -> Self {
+        let location = match Url::parse(config.url()) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Location::Url(url),
+            _ => Location::Path(project.path().join(config.url())),
+        };
+
+        Self {
+            config,
+            location,
+            cache,
+            offline: false,
+            contents: OnceCell::new(),
+        }
+    }
+
+    /// Reads the archive only from the cache instead of downloading it
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    async fn contents(&self) -> Result<&LocalLibrary, Error> {
+        self.contents.get_or_try_init(|| self.extract()).await
+    }
+
+    async fn extract(&self) -> Result<LocalLibrary, Error> {
+        let key = match &self.location {
+            Location::Url(url) => url.to_string(),
+            Location::Path(path) => path.display().to_string(),
+        };
+        let directory = self.cache.archive_path(&key);
+        let contents = directory.join("contents");
+        let extracted = std::fs::read_to_string(directory.join("sha256")).ok();
+
+        // Checksums are stored in lowercase, but can be configured in any case
+        let expected = self.config.sha256().map(str::to_ascii_lowercase);
+
+        if expected.is_some() && expected == extracted {
+            return Ok(LocalLibrary::at(root(&contents)?));
+        }
+
+        if self.offline && matches!(self.location, Location::Url(_)) {
+            return match extracted {
+                Some(_) => Ok(LocalLibrary::at(root(&contents)?)),
+                None => Err(Error::NotCached(self.config.url().into(), self.to_string())),
+            };
+        }
+
+        let archive = self.read().await?;
+        let checksum = hash(&archive);
+
+        if let Some(expected) = expected {
+            if expected != checksum {
+                return Err(Error::Checksum(
+                    self.config.url().into(),
+                    expected,
+                    checksum,
+                ));
+            }
+        }
+
+        if extracted.as_deref() != Some(checksum.as_str()) {
+            if directory.exists() {
+                std::fs::remove_dir_all(&directory)?;
+            }
+
+            unpack(&archive, &contents)?;
+            std::fs::write(directory.join("sha256"), &checksum)?;
+        }
+
+        Ok(LocalLibrary::at(root(&contents)?))
+    }
+
+    async fn read(&self) -> Result<Vec<u8>, Error> {
+        let url = match &self.location {
+            Location::Url(url) => url.clone(),
+            Location::Path(path) => return Ok(std::fs::read(path)?),
+        };
+
+        let response = reqwest::get(url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| Error::Archive(error.to_string()))?;
+        let archive = response
+            .bytes()
+            .await
+            .map_err(|error| Error::Archive(error.to_string()))?;
+
+        Ok(archive.to_vec())
+    }
+
+    /// Reports missing fragments against the archive instead of its directory in the cache
+    fn in_archive(&self, error: Error) -> Error {
+        match error {
+            Error::NotFound(name, _) => Error::NotFound(name, self.to_string()),
+            error => error,
+        }
+    }
+}
+
+impl Display for ArchiveLibrary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "archive {}", self.config.url())
+    }
+}
+
+#[async_trait]
+impl<'a> FragmentLibrary<'a> for ArchiveLibrary {
+    async fn workflow(&self, name: &'a str) -> Result<Fragment, Error> {
+        self.contents()
+            .await?
+            .workflow(name)
+            .await
+            .map_err(|error| self.in_archive(error))
+    }
+
+    async fn job(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error> {
+        self.contents()
+            .await?
+            .job(workflow, name)
+            .await
+            .map_err(|error| self.in_archive(error))
+    }
+
+    async fn workflows(&self) -> Result<Vec<String>, Error> {
+        FragmentLibrary::<'a>::workflows(self.contents().await?).await
+    }
+
+    async fn jobs(&self, workflow: &'a str) -> Result<Vec<String>, Error> {
+        self.contents()
+            .await?
+            .jobs(workflow)
+            .await
+            .map_err(|error| self.in_archive(error))
+    }
+
+    async fn step(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error> {
+        self.contents()
+            .await?
+            .step(workflow, name)
+            .await
+            .map_err(|error| self.in_archive(error))
+    }
+}
+
+/// Extracts a gzip-compressed tarball or a zip file, which are told apart by their magic bytes
+///
+/// Both formats reject entries that would be written outside of the destination.
+fn unpack(archive: &[u8], destination: &Path) -> Result<(), Error> {
+    std::fs::create_dir_all(destination)?;
+
+    if archive.starts_with(&[0x1f, 0x8b]) {
+        tar::Archive::new(GzDecoder::new(archive)).unpack(destination)?;
+    } else if archive.starts_with(b"PK\x03\x04") {
+        zip::ZipArchive::new(Cursor::new(archive))
+            .and_then(|mut zip| zip.extract(destination))
+            .map_err(|error| Error::Archive(error.to_string()))?;
+    } else {
+        return Err(Error::Archive(
+            "unsupported format, expected a .tar.gz or .zip file".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the directory that contains the fragments
+///
+/// Archives are often created from a directory, in which case all fragments are nested inside it.
+fn root(contents: &Path) -> Result<PathBuf, Error> {
+    let entries = std::fs::read_dir(contents)?.collect::<Result<Vec<_>, _>>()?;
+
+    match entries.as_slice() {
+        [entry] if entry.path().is_dir() && !entry.path().join("workflow.yml").exists() => {
+            Ok(entry.path())
+        }
+        _ => Ok(contents.to_path_buf()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use mockito::Server;
+    use tempfile::{tempdir, TempDir};
+
+    use crate::TestProject;
+
+    use super::*;
+
+    const FILES: [(&str, &str); 2] = [
+        ("rust/workflow.yml", "name: Rust\n"),
+        ("rust/lint.yml", "lint:\n  runs-on: ubuntu-latest\n"),
+    ];
+
+    fn tar_gz(prefix: &str) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+
+        for (path, content) in FILES {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+
+            builder
+                .append_data(&mut header, format!("{prefix}{path}"), content.as_bytes())
+                .unwrap();
+        }
+
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn zip() -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+
+        for (path, content) in FILES {
+            writer
+                .start_file(path, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn library(
+        project: &TestProject,
+        url: &str,
+        sha256: Option<&str>,
+        cache: &TempDir,
+    ) -> ArchiveLibrary {
+        let config = match sha256 {
+            Some(sha256) => ArchiveConfiguration::builder()
+                .url(url)
+                .sha256(sha256)
+                .build(),
+            None => ArchiveConfiguration::builder().url(url).build(),
+        };
+
+        ArchiveLibrary::new(project.project(), config, Cache::new(cache.path()))
+    }
+
+    #[tokio::test]
+    async fn workflow_from_tar_gz_over_http() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/templates.tar.gz")
+            .with_body(tar_gz(""))
+            .create_async()
+            .await;
+
+        let test_project = TestProject::new().unwrap();
+        let cache = tempdir().unwrap();
+        let url = format!("{}/templates.tar.gz", server.url());
+
+        let workflow = library(&test_project, &url, None, &cache)
+            .workflow("rust")
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!("name: Rust\n", workflow.template().get());
+    }
+
+    #[tokio::test]
+    async fn job_from_zip_file() {
+        let test_project = TestProject::new().unwrap();
+        std::fs::write(test_project.path().join("templates.zip"), zip()).unwrap();
+        let cache = tempdir().unwrap();
+
+        let job = library(&test_project, "templates.zip", None, &cache)
+            .job("rust", "lint")
+            .await
+            .unwrap();
+
+        assert_eq!("lint:\n  runs-on: ubuntu-latest\n", job.template().get());
+    }
+
+    #[tokio::test]
+    async fn workflows_from_archive_with_top_level_directory() {
+        let test_project = TestProject::new().unwrap();
+        std::fs::write(
+            test_project.path().join("templates.tar.gz"),
+            tar_gz("templates-1.0.0/"),
+        )
+        .unwrap();
+        let cache = tempdir().unwrap();
+
+        let library = library(&test_project, "templates.tar.gz", None, &cache);
+
+        assert_eq!(vec!["rust"], library.workflows().await.unwrap());
+        assert_eq!(vec!["lint"], library.jobs("rust").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn checksum_mismatch() {
+        let test_project = TestProject::new().unwrap();
+        std::fs::write(test_project.path().join("templates.zip"), zip()).unwrap();
+        let cache = tempdir().unwrap();
+
+        let error = library(&test_project, "templates.zip", Some("abc"), &cache)
+            .workflow("rust")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, Error::Checksum(_, expected, _) if expected == "abc"));
+    }
+
+    #[tokio::test]
+    async fn pinned_archive_is_downloaded_once() {
+        let archive = tar_gz("");
+        let checksum = hash(&archive);
+
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/templates.tar.gz")
+            .with_body(archive)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let test_project = TestProject::new().unwrap();
+        let cache = tempdir().unwrap();
+        let url = format!("{}/templates.tar.gz", server.url());
+
+        for _ in 0..2 {
+            library(&test_project, &url, Some(&checksum), &cache)
+                .workflow("rust")
+                .await
+                .unwrap();
+        }
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn pinned_archive_with_uppercase_checksum_is_downloaded_once() {
+        let archive = tar_gz("");
+        let checksum = hash(&archive).to_ascii_uppercase();
+
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/templates.tar.gz")
+            .with_body(archive)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let test_project = TestProject::new().unwrap();
+        let cache = tempdir().unwrap();
+        let url = format!("{}/templates.tar.gz", server.url());
+
+        for _ in 0..2 {
+            library(&test_project, &url, Some(&checksum), &cache)
+                .workflow("rust")
+                .await
+                .unwrap();
+        }
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn offline_uses_extracted_archive() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/templates.zip")
+            .with_body(zip())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let test_project = TestProject::new().unwrap();
+        let cache = tempdir().unwrap();
+        let url = format!("{}/templates.zip", server.url());
+
+        library(&test_project, &url, None, &cache)
+            .workflow("rust")
+            .await
+            .unwrap();
+        let workflow = library(&test_project, &url, None, &cache)
+            .offline(true)
+            .workflow("rust")
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!("name: Rust\n", workflow.template().get());
+    }
+
+    #[tokio::test]
+    async fn offline_without_cache() {
+        let test_project = TestProject::new().unwrap();
+        let cache = tempdir().unwrap();
+
+        let error = library(
+            &test_project,
+            "https://artifacts.example.com/templates.zip",
+            None,
+            &cache,
+        )
+        .offline(true)
+        .workflow("rust")
+        .await
+        .unwrap_err();
+
+        assert!(matches!(error, Error::NotCached(_, _)));
+    }
+
+    #[tokio::test]
+    async fn archive_not_found() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/templates.zip")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let test_project = TestProject::new().unwrap();
+        let cache = tempdir().unwrap();
+        let url = format!("{}/templates.zip", server.url());
+
+        let error = library(&test_project, &url, None, &cache)
+            .workflow("rust")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, Error::Archive(_)));
+    }
+
+    #[tokio::test]
+    async fn unsupported_format() {
+        let test_project = TestProject::new().unwrap();
+        std::fs::write(test_project.path().join("templates.txt"), "name: Rust\n").unwrap();
+        let cache = tempdir().unwrap();
+
+        let error = library(&test_project, "templates.txt", None, &cache)
+            .workflow("rust")
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "failed to read archive: unsupported format, expected a .tar.gz or .zip file",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn job_not_found() {
+        let test_project = TestProject::new().unwrap();
+        std::fs::write(test_project.path().join("templates.zip"), zip()).unwrap();
+        let cache = tempdir().unwrap();
+
+        let error = library(&test_project, "templates.zip", None, &cache)
+            .job("rust", "test")
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "failed to find 'test.yml' in archive templates.zip",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        let test_project = TestProject::new().unwrap();
+        let cache = tempdir().unwrap();
+
+        let library = library(&test_project, "templates.zip", None, &cache);
+
+        assert_eq!("archive templates.zip", library.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<ArchiveLibrary>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<ArchiveLibrary>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<ArchiveLibrary>();
+    }
+}
@@ -0,0 +1,4 @@
+pub use self::{configuration::*, library::*};
+
+mod configuration;
+mod library;
@@ -0,0 +1,52 @@
+use std::fmt::{Display, Formatter};
+
+/// The configuration of the built-in templates, which have no settings
+///
+/// The configuration is written as an empty mapping, e.g. `builtin: {}`, so that the built-in
+/// templates can be configured like every other library.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BuiltinConfiguration {}
+
+impl BuiltinConfiguration {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Display for BuiltinConfiguration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "builtin")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize() {
+        let configuration = serde_yaml::from_str::<BuiltinConfiguration>("{}").unwrap();
+
+        assert_eq!(BuiltinConfiguration::new(), configuration);
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<BuiltinConfiguration>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<BuiltinConfiguration>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<BuiltinConfiguration>();
+    }
+}
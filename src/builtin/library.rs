@@ -0,0 +1,234 @@
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+
+use crate::cache::hash;
+use crate::error::Error;
+use crate::fragment::{job_name, sidecar_path, step_paths, Fragment, FragmentLibrary};
+
+/// The templates that are compiled into FlowCrafter, by their path in the library
+const TEMPLATES: &[(&str, &str)] = &[
+    ("json/style.yml", include_str!("templates/json/style.yml")),
+    (
+        "json/workflow.yml",
+        include_str!("templates/json/workflow.yml"),
+    ),
+    (
+        "markdown/lint.yml",
+        include_str!("templates/markdown/lint.yml"),
+    ),
+    (
+        "markdown/style.yml",
+        include_str!("templates/markdown/style.yml"),
+    ),
+    (
+        "markdown/workflow.yml",
+        include_str!("templates/markdown/workflow.yml"),
+    ),
+    (
+        "rust/features.yml",
+        include_str!("templates/rust/features.yml"),
+    ),
+    ("rust/lint.yml", include_str!("templates/rust/lint.yml")),
+    ("rust/style.yml", include_str!("templates/rust/style.yml")),
+    ("rust/test.yml", include_str!("templates/rust/test.yml")),
+    (
+        "rust/workflow.yml",
+        include_str!("templates/rust/workflow.yml"),
+    ),
+    ("yaml/lint.yml", include_str!("templates/yaml/lint.yml")),
+    ("yaml/style.yml", include_str!("templates/yaml/style.yml")),
+    (
+        "yaml/workflow.yml",
+        include_str!("templates/yaml/workflow.yml"),
+    ),
+];
+
+/// A library of templates that are compiled into FlowCrafter
+///
+/// The library contains workflows to lint and check the style of JSON, Markdown, Rust, and YAML
+/// files. It does not require network access, which makes it a good starting point for new projects.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct BuiltinLibrary;
+
+impl BuiltinLibrary {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn read_fragment(&self, name: &str, path: &str) -> Result<Fragment, Error> {
+        let Some(content) = template(path) else {
+            return Err(Error::NotFound(
+                path.rsplit('/').next().unwrap_or(path).into(),
+                self.to_string(),
+            ));
+        };
+
+        let sidecar = template(&sidecar_path(path));
+
+        let mut fragment = Fragment::parse_with_sidecar(name, content, sidecar)?;
+        fragment.set_revision(hash(format!("{content}{}", sidecar.unwrap_or_default())));
+
+        Ok(fragment)
+    }
+}
+
+fn template(path: &str) -> Option<&'static str> {
+    TEMPLATES
+        .iter()
+        .find(|(template, _)| *template == path)
+        .map(|(_, content)| *content)
+}
+
+impl Display for BuiltinLibrary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "built-in templates")
+    }
+}
+
+#[async_trait]
+impl<'a> FragmentLibrary<'a> for BuiltinLibrary {
+    async fn workflow(&self, name: &'a str) -> Result<Fragment, Error> {
+        self.read_fragment(name, &format!("{name}/workflow.yml"))
+    }
+
+    async fn job(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error> {
+        self.read_fragment(name, &format!("{workflow}/{name}.yml"))
+    }
+
+    async fn workflows(&self) -> Result<Vec<String>, Error> {
+        let mut workflows: Vec<String> = TEMPLATES
+            .iter()
+            .filter_map(|(path, _)| path.strip_suffix("/workflow.yml"))
+            .map(String::from)
+            .collect();
+
+        workflows.sort();
+        Ok(workflows)
+    }
+
+    async fn jobs(&self, workflow: &'a str) -> Result<Vec<String>, Error> {
+        let prefix = format!("{workflow}/");
+
+        let files: Vec<&str> = TEMPLATES
+            .iter()
+            .filter_map(|(path, _)| path.strip_prefix(&prefix))
+            .collect();
+
+        if !files.contains(&"workflow.yml") {
+            return Err(Error::NotFound(workflow.into(), self.to_string()));
+        }
+
+        let mut jobs: Vec<String> = files
+            .into_iter()
+            .filter_map(job_name)
+            .map(String::from)
+            .collect();
+
+        jobs.sort();
+        Ok(jobs)
+    }
+
+    async fn step(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error> {
+        let [workflow_step, shared_step] = step_paths(workflow, name);
+
+        match self.read_fragment(name, &workflow_step) {
+            Err(Error::NotFound(_, _)) => self.read_fragment(name, &shared_step),
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Job, Renderer};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn workflow() {
+        let workflow = BuiltinLibrary::new().workflow("rust").await.unwrap();
+
+        assert!(workflow.template().get().contains("name: Rust"));
+        assert!(workflow.revision().is_some());
+    }
+
+    #[tokio::test]
+    async fn job_with_description() {
+        let job = BuiltinLibrary::new().job("rust", "test").await.unwrap();
+
+        assert!(job.metadata().description().is_some());
+        assert!(job.template().get().starts_with("---\ntest:"));
+    }
+
+    #[tokio::test]
+    async fn workflow_not_found() {
+        let error = BuiltinLibrary::new().workflow("python").await.unwrap_err();
+
+        assert_eq!(
+            "failed to find 'workflow.yml' in built-in templates",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn workflows() {
+        let workflows = BuiltinLibrary::new().workflows().await.unwrap();
+
+        assert_eq!(vec!["json", "markdown", "rust", "yaml"], workflows);
+    }
+
+    #[tokio::test]
+    async fn jobs() {
+        let jobs = BuiltinLibrary::new().jobs("rust").await.unwrap();
+
+        assert_eq!(vec!["features", "lint", "style", "test"], jobs);
+    }
+
+    #[tokio::test]
+    async fn jobs_of_missing_workflow() {
+        let error = BuiltinLibrary::new().jobs("python").await.unwrap_err();
+
+        assert!(matches!(error, Error::NotFound(_, _)));
+    }
+
+    #[tokio::test]
+    async fn all_workflows_render() {
+        let library = BuiltinLibrary::new();
+
+        for workflow in library.workflows().await.unwrap() {
+            let fragment = library.workflow(&workflow).await.unwrap();
+
+            let mut jobs = Vec::new();
+            for job in library.jobs(&workflow).await.unwrap() {
+                let job = library.job(&workflow, &job).await.unwrap();
+                jobs.push(Job::builder().fragment(job).build());
+            }
+
+            assert!(Renderer::new(&fragment, &jobs).render().is_ok());
+        }
+    }
+
+    #[test]
+    fn trait_display() {
+        assert_eq!("built-in templates", BuiltinLibrary::new().to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<BuiltinLibrary>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<BuiltinLibrary>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<BuiltinLibrary>();
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::Error;
+
+/// An on-disk cache for fragments that have been downloaded from a remote library
+///
+/// The contents of the fragments are stored by their revision in `objects`, so that a fragment
+/// that is shared between references is only stored once. Entries in `refs` map a key, e.g. the
+/// repository, reference, and path of a fragment, to a revision and the ETag that was returned when
+/// it was downloaded. Libraries that clone repositories keep their clones in `git`, and libraries
+/// that download archives extract them into `archives`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Cache {
+    path: PathBuf,
+}
+
+impl Cache {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the cache in the user's cache directory, e.g. `$XDG_CACHE_HOME/flowcrafter`
+    pub fn discover() -> Option<Self> {
+        dirs::cache_dir().map(|path| Self::new(path.join("flowcrafter")))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the cached entry for a key, or `None` if the key has not been cached yet
+    pub fn get(&self, key: &str) -> Option<CacheEntry> {
+        let reference = std::fs::read_to_string(self.ref_path(key)).ok()?;
+
+        let mut revision = None;
+        let mut etag = None;
+
+        for line in reference.lines() {
+            match line.split_once(' ') {
+                Some(("revision", value)) => revision = Some(value.to_string()),
+                Some(("etag", value)) => etag = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        let revision = revision?;
+        let content = std::fs::read_to_string(self.object_path(&revision)).ok()?;
+
+        Some(CacheEntry {
+            revision,
+            etag,
+            content,
+        })
+    }
+
+    pub fn insert(&self, key: &str, entry: &CacheEntry) -> Result<(), Error> {
+        let object_path = self.object_path(&entry.revision);
+        let ref_path = self.ref_path(key);
+
+        for path in [&object_path, &ref_path] {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+
+        let mut reference = format!("revision {}\n", entry.revision);
+        if let Some(etag) = &entry.etag {
+            reference.push_str(&format!("etag {etag}\n"));
+        }
+
+        std::fs::write(object_path, &entry.content)?;
+        std::fs::write(ref_path, reference)?;
+
+        Ok(())
+    }
+
+    /// Returns the directory in which the clone of a repository is kept
+    pub fn repository_path(&self, key: &str) -> PathBuf {
+        self.path.join("git").join(hash(key))
+    }
+
+    /// Returns the directory into which an archive is extracted
+    pub fn archive_path(&self, key: &str) -> PathBuf {
+        self.path.join("archives").join(hash(key))
+    }
+
+    fn object_path(&self, revision: &str) -> PathBuf {
+        self.path.join("objects").join(hash(revision))
+    }
+
+    fn ref_path(&self, key: &str) -> PathBuf {
+        self.path.join("refs").join(hash(key))
+    }
+}
+
+impl Display for Cache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cache {}", self.path.display())
+    }
+}
+
+/// A fragment in the cache
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct CacheEntry {
+    revision: String,
+    etag: Option<String>,
+    content: String,
+}
+
+impl CacheEntry {
+    pub fn new(
+        revision: impl Into<String>,
+        etag: Option<String>,
+        content: impl Into<String>,
+    ) -> Self {
+        Self {
+            revision: revision.into(),
+            etag,
+            content: content.into(),
+        }
+    }
+
+    pub fn revision(&self) -> &str {
+        &self.revision
+    }
+
+    pub fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+/// Hashes a key or revision so that it can be safely used as a file name
+pub(crate) fn hash(value: impl AsRef<[u8]>) -> String {
+    Sha256::digest(value)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn get_returns_inserted_entry() {
+        let temp_dir = tempdir().unwrap();
+        let cache = Cache::new(temp_dir.path());
+
+        let entry = CacheEntry::new("abc", Some("\"etag\"".into()), "name: Rust\n");
+        cache
+            .insert("jdno/workflows/rust/workflow.yml", &entry)
+            .unwrap();
+
+        assert_eq!(Some(entry), cache.get("jdno/workflows/rust/workflow.yml"));
+    }
+
+    #[test]
+    fn get_returns_none_for_missing_key() {
+        let temp_dir = tempdir().unwrap();
+        let cache = Cache::new(temp_dir.path());
+
+        assert!(cache.get("jdno/workflows/rust/workflow.yml").is_none());
+    }
+
+    #[test]
+    fn insert_shares_objects_between_keys() {
+        let temp_dir = tempdir().unwrap();
+        let cache = Cache::new(temp_dir.path());
+
+        let entry = CacheEntry::new("abc", None, "name: Rust\n");
+        cache.insert("v1/rust/workflow.yml", &entry).unwrap();
+        cache.insert("v2/rust/workflow.yml", &entry).unwrap();
+
+        let objects = std::fs::read_dir(temp_dir.path().join("objects")).unwrap();
+
+        assert_eq!(1, objects.count());
+    }
+
+    #[test]
+    fn trait_display() {
+        let cache = Cache::new("/tmp/flowcrafter");
+
+        assert_eq!("cache /tmp/flowcrafter", cache.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Cache>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Cache>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Cache>();
+    }
+}
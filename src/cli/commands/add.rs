@@ -0,0 +1,228 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+
+use crate::cli::{Command, Configuration, Create};
+use crate::Project;
+
+/// Adds jobs to an existing workflow and renders it again
+///
+/// The new jobs are appended to the jobs that are already configured for the workflow, so that the
+/// order of the existing jobs does not change.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Add<'a> {
+    project: &'a Project,
+    workflow: &'a str,
+    jobs: &'a [String],
+    dry_run: bool,
+    force: bool,
+    offline: bool,
+}
+
+impl<'a> Add<'a> {
+    pub fn new(project: &'a Project, workflow: &'a str, jobs: &'a [String]) -> Self {
+        Self {
+            project,
+            workflow,
+            jobs,
+            dry_run: false,
+            force: false,
+            offline: false,
+        }
+    }
+
+    /// Prints the changes to the workflow and the configuration instead of writing them to disk
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Overwrites the workflow even if it has been modified by hand
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Reads templates only from the cache instead of downloading them
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    fn merge_jobs(&self, configuration: &Configuration) -> Result<Vec<String>, Error> {
+        let workflow = configuration
+            .workflows()
+            .iter()
+            .find(|workflow| workflow.name() == self.workflow)
+            .ok_or_else(|| anyhow!("workflow '{}' is not configured", self.workflow))?;
+
+        let mut jobs: Vec<String> = workflow
+            .jobs()
+            .iter()
+            .map(|job| job.key().to_string())
+            .collect();
+
+        for job in self.jobs {
+            if jobs.contains(job) {
+                return Err(anyhow!(
+                    "job '{job}' is already part of workflow '{}'",
+                    self.workflow
+                ));
+            }
+
+            jobs.push(job.clone());
+        }
+
+        Ok(jobs)
+    }
+}
+
+#[async_trait]
+impl<'a> Command for Add<'a> {
+    async fn run(&self) -> Result<(), Error> {
+        let configuration = Configuration::load(self.project)?;
+        let jobs = self.merge_jobs(&configuration)?;
+
+        Create::new(self.project, self.workflow, &jobs)
+            .dry_run(self.dry_run)
+            .force(self.force)
+            .offline(self.offline)
+            .run()
+            .await
+    }
+}
+
+impl Display for Add<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "add -w {}", self.workflow)?;
+
+        for job in self.jobs {
+            write!(f, " -j {job}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::commands::local_project;
+
+    use super::*;
+
+    async fn create_workflow(project: &Project) {
+        for job in ["style", "test"] {
+            std::fs::write(
+                project.path().join(format!("templates/rust/{job}.yml")),
+                format!("{job}:\n  runs-on: ubuntu-latest\n"),
+            )
+            .unwrap();
+        }
+
+        let jobs = vec!["test".into(), "lint".into()];
+        Create::new(project, "rust", &jobs).run().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn run_appends_jobs() {
+        let test_project = local_project();
+        create_workflow(test_project.project()).await;
+
+        let jobs = vec!["style".into()];
+        Add::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        let configuration = Configuration::load(test_project.project()).unwrap();
+        let jobs: Vec<&str> = configuration.workflows()[0]
+            .jobs()
+            .iter()
+            .map(|job| job.name())
+            .collect();
+        let workflow =
+            std::fs::read_to_string(test_project.path().join(".github/workflows/rust.yml"))
+                .unwrap();
+
+        assert_eq!(vec!["test", "lint", "style"], jobs);
+        assert!(workflow.contains("style:"));
+    }
+
+    #[tokio::test]
+    async fn run_errors_for_duplicate_job() {
+        let test_project = local_project();
+        create_workflow(test_project.project()).await;
+
+        let jobs = vec!["lint".into()];
+        let error = Add::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "job 'lint' is already part of workflow 'rust'",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_errors_for_job_given_twice() {
+        let test_project = local_project();
+        create_workflow(test_project.project()).await;
+
+        let jobs = vec!["style".into(), "style".into()];
+        let error = Add::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "job 'style' is already part of workflow 'rust'",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_errors_for_unknown_workflow() {
+        let test_project = local_project();
+
+        let jobs = vec!["lint".into()];
+        let error = Add::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!("workflow 'rust' is not configured", error.to_string());
+    }
+
+    #[test]
+    fn trait_display() {
+        let project = Project::at(".".into()).unwrap();
+
+        let jobs = vec!["lint".into(), "test".into()];
+
+        assert_eq!(
+            "add -w rust -j lint -j test",
+            Add::new(&project, "rust", &jobs).to_string()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Add>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Add>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Add>();
+    }
+}
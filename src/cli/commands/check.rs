@@ -0,0 +1,200 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::{anyhow, Context, Error};
+use async_trait::async_trait;
+
+use crate::cli::commands::{configured_workflows, create_commands, render_workflows};
+use crate::cli::{Command, Configuration, Create, Lockfile};
+use crate::Project;
+
+/// Checks that the workflows on disk match what would be rendered from the configuration
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Check<'a> {
+    project: &'a Project,
+    offline: bool,
+}
+
+impl<'a> Check<'a> {
+    pub fn new(project: &'a Project) -> Self {
+        Self {
+            project,
+            offline: false,
+        }
+    }
+
+    /// Reads templates only from the cache instead of downloading them
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+}
+
+#[async_trait]
+impl<'a> Command for Check<'a> {
+    async fn run(&self) -> Result<(), Error> {
+        let configuration = Configuration::load(self.project)?;
+        let mut outdated = Vec::new();
+
+        let workflows = configured_workflows(&configuration);
+
+        let commands: Vec<Create> = create_commands(self.project, &workflows)
+            .map(|command| command.offline(self.offline))
+            .collect();
+
+        let lockfile = Lockfile::load(self.project)?;
+        let renders = render_workflows(
+            self.project,
+            &configuration,
+            &commands,
+            &lockfile,
+            self.offline,
+        )
+        .await;
+
+        for (command, render) in commands.iter().zip(renders) {
+            let path = command.workflow_path();
+            let relative_path = command.relative_workflow_path();
+
+            let rendered_workflow = match render {
+                Ok((rendered_workflow, _)) => rendered_workflow,
+                Err(error) => {
+                    outdated.push(format!("{} ({error:#})", relative_path.display()));
+                    continue;
+                }
+            };
+
+            if !path.exists() {
+                outdated.push(format!("{} (missing)", relative_path.display()));
+                continue;
+            }
+
+            let existing_workflow = std::fs::read_to_string(&path)
+                .context(format!("failed to read {}", relative_path.display()))?;
+
+            if existing_workflow != rendered_workflow.to_string() {
+                outdated.push(format!("{} (modified)", relative_path.display()));
+            }
+        }
+
+        if outdated.is_empty() {
+            return Ok(());
+        }
+
+        Err(anyhow!(
+            "{} workflow(s) differ from the configuration:\n  {}",
+            outdated.len(),
+            outdated.join("\n  ")
+        ))
+    }
+}
+
+impl Display for Check<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "check")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::commands::local_project;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn run_succeeds_when_workflows_are_up_to_date() {
+        let test_project = local_project();
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        assert!(Check::new(test_project.project()).run().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn run_reports_modified_workflows() {
+        let test_project = local_project();
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        std::fs::write(
+            test_project.path().join(".github/workflows/rust.yml"),
+            "name: Edited by hand\n",
+        )
+        .unwrap();
+
+        let error = Check::new(test_project.project()).run().await.unwrap_err();
+
+        assert_eq!(
+            "1 workflow(s) differ from the configuration:\n  .github/workflows/rust.yml (modified)",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_reports_workflows_that_fail_to_render() {
+        let test_project = local_project();
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        std::fs::remove_file(test_project.path().join("templates/rust/lint.yml")).unwrap();
+
+        let error = Check::new(test_project.project()).run().await.unwrap_err();
+
+        assert!(error.to_string().starts_with(
+            "1 workflow(s) differ from the configuration:\n  .github/workflows/rust.yml (failed to download job 'lint'"
+        ));
+    }
+
+    #[tokio::test]
+    async fn run_reports_missing_workflows() {
+        let test_project = local_project();
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        std::fs::remove_file(test_project.path().join(".github/workflows/rust.yml")).unwrap();
+
+        let error = Check::new(test_project.project()).run().await.unwrap_err();
+
+        assert!(error.to_string().contains("rust.yml (missing)"));
+    }
+
+    #[test]
+    fn trait_display() {
+        let project = Project::at(".".into()).unwrap();
+
+        assert_eq!("check", Check::new(&project).to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Check>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Check>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Check>();
+    }
+}
@@ -1,20 +1,32 @@
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::ops::Deref;
+use std::path::PathBuf;
 
-use anyhow::{Context, Error};
+use anyhow::{anyhow, Context, Error};
 use async_trait::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
+use serde_yaml::Value;
 
-use crate::cli::configuration::WorkflowConfiguration;
-use crate::cli::{Command, Configuration, LibraryConfiguration};
-use crate::github::GitHubLibrary;
-use crate::local::LocalLibrary;
-use crate::{Error as CrateError, Fragment, FragmentLibrary, Project, Renderer, Workflow};
+use crate::cli::commands::{diff_file, print_configuration_diff, MAX_CONCURRENT_DOWNLOADS};
+use crate::cli::configuration::{JobConfiguration, Libraries, WorkflowConfiguration};
+use crate::cli::{Command, Configuration, LockedWorkflow, Lockfile};
+use crate::fragment::{job_reference_name, split_reference};
+use crate::{
+    sort_by_dependencies, Error as CrateError, Fragment, FragmentLibrary, Job, Project, Renderer,
+    Variables, Workflow,
+};
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct Create<'a> {
     project: &'a Project,
     workflow: &'a str,
     jobs: &'a [String],
+    locked: bool,
+    refresh: bool,
+    dry_run: bool,
+    force: bool,
+    offline: bool,
 }
 
 impl<'a> Create<'a> {
@@ -23,98 +35,474 @@ impl<'a> Create<'a> {
             project,
             workflow,
             jobs,
+            locked: false,
+            refresh: false,
+            dry_run: false,
+            force: false,
+            offline: false,
         }
     }
 
+    /// Fails instead of updating the lockfile when the templates differ from the locked revisions
+    pub fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Reads the templates from the latest commits of the libraries instead of the locked commits
+    pub fn refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Prints the changes to the workflow and the configuration instead of writing them to disk
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Overwrites the workflow even if it has been modified by hand
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Reads templates only from the cache instead of downloading them
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
     async fn download_fragments(
+        &self,
+        library: &dyn for<'b> FragmentLibrary<'b>,
+        workflow_configuration: &WorkflowConfiguration,
+    ) -> Result<(Fragment, Vec<Job>), Error> {
+        futures::try_join!(
+            self.get_workflow(library),
+            self.get_jobs(library, workflow_configuration)
+        )
+    }
+
+    /// Returns the libraries for the workflow, which can override the reference of the libraries
+    ///
+    /// A workflow that has been locked is rendered from the locked commits, unless its libraries
+    /// have changed since or the lockfile is refreshed.
+    pub(crate) fn libraries(
         &self,
         configuration: &Configuration,
-    ) -> Result<(Fragment, Vec<Fragment>), Error> {
-        let library = self.init_library(configuration);
+        workflow_configuration: &WorkflowConfiguration,
+        lockfile: &Lockfile,
+    ) -> Result<Libraries, Error> {
+        let mut libraries = configuration.libraries().clone();
+
+        if let Some(reference) = workflow_configuration.reference() {
+            libraries
+                .set_reference(reference, workflow_configuration.name())
+                .map_err(Error::msg)?;
+        }
 
-        let workflow = self.get_workflow(library.deref()).await?;
-        let jobs = self.get_jobs(library.deref()).await?;
+        if let Some(locked_workflow) = lockfile
+            .workflow(self.workflow_name())
+            .filter(|locked_workflow| locked_workflow.source() == libraries.to_string())
+            .filter(|_| !self.refresh)
+        {
+            libraries.pin(locked_workflow.commits());
+        }
 
-        Ok((workflow, jobs))
+        Ok(libraries)
     }
 
-    fn init_library(&self, configuration: &'a Configuration) -> Box<dyn FragmentLibrary<'a>> {
-        match configuration.library() {
-            LibraryConfiguration::GitHub(github_configuration) => {
-                Box::new(GitHubLibrary::new(github_configuration.clone()))
-            }
-            LibraryConfiguration::Local(local_configuration) => {
-                Box::new(LocalLibrary::new(self.project, local_configuration))
+    /// Returns the commits that the libraries of the workflow read their fragments from
+    async fn commits(
+        &self,
+        library: &dyn for<'b> FragmentLibrary<'b>,
+        libraries: &Libraries,
+    ) -> Result<BTreeMap<String, String>, Error> {
+        let mut commits = BTreeMap::new();
+
+        for named_library in libraries.libraries() {
+            let commit = library
+                .commit(Some(named_library.name()))
+                .await
+                .context(format!("failed to resolve the commit of {library}"))?;
+
+            if let Some(commit) = commit {
+                commits.insert(named_library.name().to_string(), commit);
             }
         }
+
+        Ok(commits)
     }
 
-    async fn get_workflow(&self, library: &dyn FragmentLibrary<'a>) -> Result<Fragment, Error> {
+    async fn get_workflow(
+        &self,
+        library: &dyn for<'b> FragmentLibrary<'b>,
+    ) -> Result<Fragment, Error> {
         library.workflow(self.workflow).await.context(format!(
-            "failed to download workflow '{}' from GitHub",
+            "failed to download workflow '{}' from {library}",
             self.workflow
         ))
     }
 
-    async fn get_jobs(&self, library: &dyn FragmentLibrary<'a>) -> Result<Vec<Fragment>, Error> {
-        let mut jobs = Vec::new();
+    /// Downloads the jobs and the jobs that they require, and orders them by their dependencies
+    ///
+    /// Requirements that are not in the library are left to the renderer, since they can refer to
+    /// jobs in the workflow template.
+    async fn get_jobs(
+        &self,
+        library: &dyn for<'b> FragmentLibrary<'b>,
+        workflow_configuration: &WorkflowConfiguration,
+    ) -> Result<Vec<Job>, Error> {
+        let mut jobs: Vec<Job> = self
+            .download_jobs(library, self.jobs, workflow_configuration, true)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<Option<Job>>, Error>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        let mut unresolved = Vec::new();
+
+        loop {
+            let requirements = missing_requirements(&jobs, &unresolved);
+            if requirements.is_empty() {
+                break;
+            }
+
+            let downloads = self
+                .download_jobs(library, &requirements, workflow_configuration, false)
+                .await;
 
-        for job in self.jobs {
-            let job = library
-                .job(self.workflow, job)
-                .await
-                .context(format!("failed to download job '{}' from GitHub", job))?;
+            for (requirement, download) in requirements.into_iter().zip(downloads) {
+                match download? {
+                    Some(job) => jobs.push(job),
+                    None => unresolved.push(requirement),
+                }
+            }
+        }
+
+        Ok(sort_by_dependencies(jobs)?)
+    }
 
-            jobs.push(job);
+    /// Downloads the jobs concurrently, while keeping them in the order in which they were given
+    async fn download_jobs(
+        &self,
+        library: &dyn for<'b> FragmentLibrary<'b>,
+        names: &[String],
+        workflow_configuration: &WorkflowConfiguration,
+        required: bool,
+    ) -> Vec<Result<Option<Job>, Error>> {
+        let downloads: Vec<_> = names
+            .iter()
+            .map(|name| self.get_job(library, name, workflow_configuration, required))
+            .collect();
+
+        stream::iter(downloads)
+            .buffered(MAX_CONCURRENT_DOWNLOADS)
+            .collect()
+            .await
+    }
+
+    /// Downloads a job, which is either configured for the workflow or one of its requirements
+    ///
+    /// Configured jobs are looked up by their id, so that a job that has been added to the workflow
+    /// more than once is downloaded with the variables of each of its instances.
+    ///
+    /// Returns `None` if a job that is not required does not exist in the library. Any other
+    /// error, including a missing step of the job, is returned.
+    async fn get_job(
+        &self,
+        library: &dyn for<'b> FragmentLibrary<'b>,
+        key: &str,
+        workflow_configuration: &WorkflowConfiguration,
+        required: bool,
+    ) -> Result<Option<Job>, Error> {
+        let configuration = workflow_configuration
+            .jobs()
+            .iter()
+            .find(|job| job.key() == key);
+        let name = configuration.map(JobConfiguration::name).unwrap_or(key);
+
+        let fragment = match library.job(self.workflow, name).await {
+            Ok(fragment) => fragment,
+            Err(CrateError::NotFound(_, _)) if !required => return Ok(None),
+            Err(error) => {
+                return Err(Error::new(error)
+                    .context(format!("failed to download job '{name}' from {library}")))
+            }
+        };
+
+        if !fragment.metadata().is_compatible_with(self.workflow_name()) {
+            return Err(anyhow!(
+                "job '{name}' is not compatible with workflow '{}', only with {}",
+                self.workflow_name(),
+                fragment.metadata().workflows().join(", ")
+            ));
         }
 
-        Ok(jobs)
+        let variables = configuration
+            .map(|job| job.variables().clone())
+            .unwrap_or_default();
+        let steps = self
+            .get_steps(
+                library,
+                &fragment,
+                &workflow_configuration.variables().merge(&variables),
+            )
+            .await?;
+
+        Ok(Some(
+            Job::builder()
+                .fragment(fragment)
+                .steps(steps)
+                .id(configuration.and_then(|job| job.id()).map(String::from))
+                .variables(variables)
+                .matrix(
+                    configuration
+                        .map(|job| job.matrix().clone())
+                        .unwrap_or_default(),
+                )
+                .build(),
+        ))
+    }
+
+    /// Downloads the steps that a job references concurrently
+    ///
+    /// The references are read from the job after it has been interpolated with its variables,
+    /// which is how the renderer reads them as well.
+    async fn get_steps(
+        &self,
+        library: &dyn for<'b> FragmentLibrary<'b>,
+        job: &Fragment,
+        variables: &Variables,
+    ) -> Result<Vec<Fragment>, Error> {
+        let mut interpolated = job.clone();
+        interpolated.set_template(job.template().interpolate(
+            job.name(),
+            job.variables(),
+            variables,
+        )?);
+
+        let downloads: Vec<_> = interpolated
+            .steps()?
+            .into_iter()
+            .map(|name| async move {
+                library.step(self.workflow, &name).await.context(format!(
+                    "failed to download step '{name}' for job '{job}' from {library}"
+                ))
+            })
+            .collect();
+
+        stream::iter(downloads)
+            .buffered(MAX_CONCURRENT_DOWNLOADS)
+            .try_collect()
+            .await
     }
 
     fn render_workflow(
         &self,
         workflow: &Fragment,
-        jobs: &[Fragment],
+        jobs: &[Job],
+        workflow_configuration: &WorkflowConfiguration,
     ) -> Result<Workflow, CrateError> {
-        let renderer = Renderer::new(workflow, jobs);
+        let renderer = Renderer::new(workflow, jobs)
+            .variables(workflow_configuration.variables())
+            .duplicates(workflow_configuration.duplicates());
         renderer.render()
     }
 
-    fn save_workflow(&self, workflow: &Workflow) -> Result<(), Error> {
-        let path = self
-            .project
+    /// Renders the workflow in memory, without writing any files
+    ///
+    /// Returns the rendered workflow and the revisions of the templates that it was rendered from.
+    pub(crate) async fn render(
+        &self,
+        configuration: &Configuration,
+        workflow_configuration: &WorkflowConfiguration,
+        lockfile: &Lockfile,
+    ) -> Result<(Workflow, LockedWorkflow), Error> {
+        let libraries = self.libraries(configuration, workflow_configuration, lockfile)?;
+        let library = libraries.init(self.project, self.offline);
+
+        self.render_from(library.deref(), &libraries, workflow_configuration)
+            .await
+    }
+
+    /// Renders the workflow with fragments from a library that can be shared between workflows
+    pub(crate) async fn render_from(
+        &self,
+        library: &dyn for<'b> FragmentLibrary<'b>,
+        libraries: &Libraries,
+        workflow_configuration: &WorkflowConfiguration,
+    ) -> Result<(Workflow, LockedWorkflow), Error> {
+        let (workflow, jobs) = self
+            .download_fragments(library, workflow_configuration)
+            .await?;
+
+        let rendered_workflow = self.render_workflow(&workflow, &jobs, workflow_configuration)?;
+        if !has_jobs(&rendered_workflow) {
+            return Err(anyhow!(
+                "workflow '{0}' would have no jobs; add jobs with -j, or use `remove -w {0}` to \
+                 remove it",
+                self.workflow
+            ));
+        }
+
+        let name = self.relative_workflow_path().display().to_string();
+        for warning in rendered_workflow.validate(&name)? {
+            eprintln!("warning: {name}: {warning}");
+        }
+        let locked_workflow = LockedWorkflow::new(libraries.to_string(), &workflow, &jobs)
+            .with_commits(self.commits(library, libraries).await?);
+
+        Ok((rendered_workflow, locked_workflow))
+    }
+
+    /// Returns the name of the workflow without the library that it is read from
+    ///
+    /// A workflow that is referenced as `org:rust` is written to `rust.yml`, and locked as `rust`.
+    pub(crate) fn workflow_name(&self) -> &'a str {
+        split_reference(self.workflow).1
+    }
+
+    pub(crate) fn workflow_path(&self) -> PathBuf {
+        self.project
             .path()
             .join(".github")
             .join("workflows")
-            .join(format!("{}.yml", self.workflow));
+            .join(format!("{}.yml", self.workflow_name()))
+    }
+
+    /// Checks if the workflow file has been modified by hand since it was generated
+    pub(crate) fn is_modified(&self) -> Result<bool, Error> {
+        let path = self.workflow_path();
 
-        std::fs::write(path, workflow.to_string()).context("failed to write workflow file")
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let contents = std::fs::read_to_string(&path).context("failed to read workflow file")?;
+
+        Ok(Workflow::is_modified(&contents))
+    }
+
+    pub(crate) fn relative_workflow_path(&self) -> PathBuf {
+        let path = self.workflow_path();
+
+        path.strip_prefix(self.project.path())
+            .map(PathBuf::from)
+            .unwrap_or(path)
+    }
+
+    fn save_workflow(&self, workflow: &Workflow) -> Result<(), Error> {
+        std::fs::write(self.workflow_path(), workflow.to_string())
+            .context("failed to write workflow file")
     }
 
-    fn update_configuration(&self, configuration: &mut Configuration) -> Result<(), Error> {
+    /// Verifies the revisions of the templates if locked, or records them in the lockfile otherwise
+    pub(crate) fn update_lockfile(
+        &self,
+        locked_workflow: LockedWorkflow,
+        lockfile: &mut Lockfile,
+    ) -> Result<(), Error> {
+        if self.locked {
+            return locked_workflow.verify(lockfile.workflow(self.workflow_name()));
+        }
+
+        lockfile.add_workflow(locked_workflow);
+        Ok(())
+    }
+
+    /// Writes the workflow to disk, or prints the changes to it when running in dry-run mode
+    pub(crate) fn write(&self, workflow: &Workflow) -> Result<(), Error> {
+        if !self.dry_run {
+            return self.save_workflow(workflow);
+        }
+
+        if let Some(diff) = self.diff(workflow)? {
+            print!("{diff}");
+        }
+
+        Ok(())
+    }
+
+    fn update_configuration(&self, configuration: &mut Configuration) -> WorkflowConfiguration {
         let workflow = WorkflowConfiguration::builder()
             .name(self.workflow)
-            .jobs(self.jobs.to_vec())
+            .jobs(self.jobs.iter().map(JobConfiguration::from).collect())
             .build();
 
-        configuration.add_workflow(workflow);
-        configuration.save(self.project)
+        configuration.add_workflow(workflow).clone()
+    }
+
+    /// Returns a unified diff of the changes that would be written to the workflow
+    pub(crate) fn diff(&self, workflow: &Workflow) -> Result<Option<String>, Error> {
+        diff_file(self.project, &self.workflow_path(), &workflow.to_string())
+    }
+}
+
+/// Returns the requirements of the jobs that have neither been downloaded nor failed to download
+fn missing_requirements(jobs: &[Job], unresolved: &[String]) -> Vec<String> {
+    let mut requirements: Vec<String> = Vec::new();
+
+    for requirement in jobs
+        .iter()
+        .flat_map(|job| job.fragment().metadata().requires())
+    {
+        let name = job_reference_name(requirement);
+
+        if !jobs.iter().any(|job| job.fragment().name() == name)
+            && !unresolved.contains(requirement)
+            && !requirements.contains(requirement)
+        {
+            requirements.push(requirement.clone());
+        }
+    }
+
+    requirements
+}
+
+/// Checks if a rendered workflow has any jobs, leaving workflows that cannot be parsed to validation
+fn has_jobs(workflow: &Workflow) -> bool {
+    match serde_yaml::from_str::<Value>(workflow.get()) {
+        Ok(workflow) => workflow
+            .get("jobs")
+            .and_then(Value::as_mapping)
+            .is_some_and(|jobs| !jobs.is_empty()),
+        Err(_) => true,
     }
 }
 
 #[async_trait]
 impl<'a> Command for Create<'a> {
     async fn run(&self) -> Result<(), Error> {
+        if !self.force && !self.dry_run && self.is_modified()? {
+            return Err(anyhow!(
+                "{} has been modified by hand, use --force to overwrite it",
+                self.relative_workflow_path().display()
+            ));
+        }
+
         let mut configuration = Configuration::load(self.project)?;
+        let workflow_configuration = self.update_configuration(&mut configuration);
 
-        let (workflow, jobs) = self.download_fragments(&configuration).await?;
+        let mut lockfile = Lockfile::load(self.project)?;
+        let (rendered_workflow, locked_workflow) = self
+            .render(&configuration, &workflow_configuration, &lockfile)
+            .await?;
 
-        let rendered_workflow = self.render_workflow(&workflow, &jobs)?;
-        self.save_workflow(&rendered_workflow)?;
+        self.update_lockfile(locked_workflow, &mut lockfile)?;
 
-        self.update_configuration(&mut configuration)?;
+        self.write(&rendered_workflow)?;
 
-        Ok(())
+        if self.dry_run {
+            return print_configuration_diff(self.project, &configuration);
+        }
+        if !self.locked {
+            lockfile.save(self.project)?;
+        }
+
+        configuration.save(self.project)
     }
 }
 
@@ -133,6 +521,10 @@ impl Display for Create<'_> {
 mod tests {
     use tempfile::tempdir;
 
+    use crate::cli::commands::local_project;
+    use crate::cli::LibraryConfiguration;
+    use crate::github::GitHubConfiguration;
+
     use super::*;
 
     #[tokio::test]
@@ -158,6 +550,535 @@ mod tests {
         assert_eq!("failed to read configuration file", error.to_string());
     }
 
+    #[tokio::test]
+    async fn run_writes_lockfile() {
+        let test_project = local_project();
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        let lockfile = Lockfile::load(test_project.project()).unwrap();
+        let workflow = lockfile.workflow("rust").unwrap();
+
+        assert_eq!("local templates", workflow.source());
+        assert_eq!("lint", workflow.jobs()[0].name());
+        assert!(workflow.jobs()[0].revision().is_some());
+    }
+
+    #[tokio::test]
+    async fn run_with_named_libraries() {
+        let test_project = local_project();
+
+        let overrides = test_project.path().join("overrides/rust");
+        std::fs::create_dir_all(&overrides).unwrap();
+        std::fs::write(
+            overrides.join("lint.yml"),
+            "lint:\n  runs-on: self-hosted\n",
+        )
+        .unwrap();
+        std::fs::write(
+            Configuration::path(test_project.project()),
+            indoc::indoc!(
+                r#"
+                libraries:
+                  - name: local
+                    local:
+                      path: overrides
+                  - name: org
+                    local:
+                      path: templates
+                "#
+            ),
+        )
+        .unwrap();
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "org:rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        let workflow =
+            std::fs::read_to_string(test_project.path().join(".github/workflows/rust.yml"))
+                .unwrap();
+        let lockfile = Lockfile::load(test_project.project()).unwrap();
+
+        assert!(workflow.contains("self-hosted"));
+        assert!(lockfile.workflow("rust").is_some());
+    }
+
+    #[tokio::test]
+    async fn run_errors_on_incompatible_job() {
+        let test_project = local_project();
+
+        std::fs::write(
+            test_project.path().join("templates/rust/lint.meta.yml"),
+            "workflows: [python]\n",
+        )
+        .unwrap();
+
+        let jobs = vec!["lint".into()];
+        let error = Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "job 'lint' is not compatible with workflow 'rust', only with python",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_adds_required_jobs() {
+        let test_project = local_project();
+        let templates = test_project.path().join("templates/rust");
+
+        std::fs::write(
+            templates.join("build.yml"),
+            "build:\n  runs-on: ubuntu-latest\n",
+        )
+        .unwrap();
+        std::fs::write(
+            templates.join("test.yml"),
+            "---\nrequires: [build]\n---\ntest:\n  runs-on: ubuntu-latest\n",
+        )
+        .unwrap();
+
+        let jobs = vec!["test".into(), "lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        let workflow =
+            std::fs::read_to_string(test_project.path().join(".github/workflows/rust.yml"))
+                .unwrap();
+        let configuration = Configuration::load(test_project.project()).unwrap();
+
+        assert!(workflow.ends_with(indoc::indoc!(
+            r#"
+            jobs:
+              build:
+                runs-on: ubuntu-latest
+
+              test:
+                needs: build
+                runs-on: ubuntu-latest
+
+              lint:
+                runs-on: ubuntu-latest
+            "#
+        )));
+        assert_eq!(2, configuration.workflows()[0].jobs().len());
+    }
+
+    #[tokio::test]
+    async fn run_splices_steps() {
+        let test_project = local_project();
+        let templates = test_project.path().join("templates");
+
+        std::fs::create_dir(templates.join("steps")).unwrap();
+        std::fs::write(
+            templates.join("steps/checkout.yml"),
+            "- uses: actions/checkout@v4\n",
+        )
+        .unwrap();
+        std::fs::write(
+            templates.join("rust/lint.yml"),
+            "lint:\n  runs-on: ubuntu-latest\n  steps:\n    - step: checkout\n    - run: cargo clippy\n",
+        )
+        .unwrap();
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        let workflow =
+            std::fs::read_to_string(test_project.path().join(".github/workflows/rust.yml"))
+                .unwrap();
+        let lockfile = Lockfile::load(test_project.project()).unwrap();
+
+        assert!(workflow.contains("    - uses: actions/checkout@v4\n      - run: cargo clippy\n"));
+        assert_eq!(
+            "checkout",
+            lockfile.workflow("rust").unwrap().steps()[0].name()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_splices_steps_into_job_with_variables() {
+        let test_project = local_project();
+        let templates = test_project.path().join("templates");
+
+        std::fs::create_dir(templates.join("steps")).unwrap();
+        std::fs::write(
+            templates.join("steps/checkout.yml"),
+            "- uses: actions/checkout@v4\n",
+        )
+        .unwrap();
+        std::fs::write(
+            templates.join("rust/lint.yml"),
+            "---\nvariables:\n  runner: ubuntu\n---\nlint:\n  runs-on: {{ runner }}-latest\n  steps:\n    - step: checkout\n",
+        )
+        .unwrap();
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        let workflow =
+            std::fs::read_to_string(test_project.path().join(".github/workflows/rust.yml"))
+                .unwrap();
+
+        assert!(workflow.contains("runs-on: ubuntu-latest\n"));
+        assert!(workflow.contains("- uses: actions/checkout@v4\n"));
+    }
+
+    #[tokio::test]
+    async fn run_errors_on_missing_step() {
+        let test_project = local_project();
+
+        std::fs::write(
+            test_project.path().join("templates/rust/lint.yml"),
+            "lint:\n  runs-on: ubuntu-latest\n  steps:\n    - step: checkout\n",
+        )
+        .unwrap();
+
+        let jobs = vec!["lint".into()];
+        let error = Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("failed to download step 'checkout' for job 'lint' from path"));
+        assert!(matches!(
+            error.downcast_ref::<CrateError>(),
+            Some(CrateError::NotFound(_, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn run_errors_on_missing_step_of_required_job() {
+        let test_project = local_project();
+        let templates = test_project.path().join("templates/rust");
+
+        std::fs::write(
+            templates.join("build.yml"),
+            "build:\n  runs-on: ubuntu-latest\n  steps:\n    - step: checkout\n",
+        )
+        .unwrap();
+        std::fs::write(
+            templates.join("test.yml"),
+            "---\nrequires: [build]\n---\ntest:\n  runs-on: ubuntu-latest\n",
+        )
+        .unwrap();
+
+        let jobs = vec!["test".into()];
+        let error = Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("failed to download step 'checkout' for job 'build' from path"));
+    }
+
+    #[tokio::test]
+    async fn run_errors_without_jobs() {
+        let test_project = local_project();
+
+        let error = Create::new(test_project.project(), "rust", &[])
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "workflow 'rust' would have no jobs; add jobs with -j, or use `remove -w rust` to \
+             remove it",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_errors_on_invalid_workflow() {
+        let test_project = local_project();
+
+        std::fs::write(
+            test_project.path().join("templates/rust/lint.yml"),
+            "lint:\n  runs-on: ubuntu-latest\n  steps:\n    - uses: actions/checkout@v4\n      run: make\n",
+        )
+        .unwrap();
+
+        let jobs = vec!["lint".into()];
+        let error = Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with(".github/workflows/rust.yml is not a valid workflow:"));
+    }
+
+    #[tokio::test]
+    async fn run_accepts_unknown_keys() {
+        let test_project = local_project();
+
+        std::fs::write(
+            test_project.path().join("templates/rust/lint.yml"),
+            "lint:\n  runs-on: ubuntu-latest\n  added-after-release: true\n",
+        )
+        .unwrap();
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        assert!(test_project
+            .path()
+            .join(".github/workflows/rust.yml")
+            .exists());
+    }
+
+    #[tokio::test]
+    async fn run_errors_on_dependency_cycle() {
+        let test_project = local_project();
+        let templates = test_project.path().join("templates/rust");
+
+        std::fs::write(templates.join("lint.meta.yml"), "requires: [style]\n").unwrap();
+        std::fs::write(
+            templates.join("style.yml"),
+            "---\nrequires: [lint]\n---\nstyle:\n  runs-on: ubuntu-latest\n",
+        )
+        .unwrap();
+
+        let jobs = vec!["lint".into()];
+        let error = Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<CrateError>(),
+            Some(CrateError::DependencyCycle(cycle)) if cycle == &["lint", "style", "lint"]
+        ));
+    }
+
+    #[tokio::test]
+    async fn run_locked_errors_when_templates_changed() {
+        let test_project = local_project();
+
+        let jobs = vec!["lint".into()];
+        let command = Create::new(test_project.project(), "rust", &jobs);
+        command.run().await.unwrap();
+
+        std::fs::write(
+            test_project.path().join("templates/rust/lint.yml"),
+            "lint:\n  runs-on: macos-latest\n",
+        )
+        .unwrap();
+
+        let error = command.locked(true).run().await.unwrap_err();
+
+        assert_eq!(
+            "templates for workflow 'rust' differ from flowcrafter.lock: job 'lint' changed",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_dry_run_does_not_write_files() {
+        let test_project = local_project();
+        let configuration_path = test_project.path().join(".github/flowcrafter.yml");
+        let configuration = std::fs::read_to_string(&configuration_path).unwrap();
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .dry_run(true)
+            .run()
+            .await
+            .unwrap();
+
+        assert!(!test_project
+            .path()
+            .join(".github/workflows/rust.yml")
+            .exists());
+        assert!(!test_project
+            .path()
+            .join(".github/flowcrafter.lock")
+            .exists());
+        assert_eq!(
+            configuration,
+            std::fs::read_to_string(&configuration_path).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_errors_when_workflow_modified_by_hand() {
+        let test_project = local_project();
+        let workflow_path = test_project.path().join(".github/workflows/rust.yml");
+
+        let jobs = vec!["lint".into()];
+        let command = Create::new(test_project.project(), "rust", &jobs);
+        command.run().await.unwrap();
+
+        let contents = std::fs::read_to_string(&workflow_path).unwrap();
+        std::fs::write(&workflow_path, contents.replace("Rust", "Edited by hand")).unwrap();
+
+        let error = command.run().await.unwrap_err();
+
+        assert_eq!(
+            ".github/workflows/rust.yml has been modified by hand, use --force to overwrite it",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_force_overwrites_workflow_modified_by_hand() {
+        let test_project = local_project();
+        let workflow_path = test_project.path().join(".github/workflows/rust.yml");
+
+        let jobs = vec!["lint".into()];
+        let command = Create::new(test_project.project(), "rust", &jobs);
+        command.run().await.unwrap();
+
+        let contents = std::fs::read_to_string(&workflow_path).unwrap();
+        std::fs::write(&workflow_path, contents.replace("Rust", "Edited by hand")).unwrap();
+
+        command.force(true).run().await.unwrap();
+
+        assert_eq!(contents, std::fs::read_to_string(&workflow_path).unwrap());
+    }
+
+    #[tokio::test]
+    async fn diff_compares_with_existing_files() {
+        let test_project = local_project();
+
+        let jobs = vec!["lint".into()];
+        let command = Create::new(test_project.project(), "rust", &jobs);
+        command.run().await.unwrap();
+
+        std::fs::write(
+            test_project.path().join("templates/rust/workflow.yml"),
+            "name: Rust CI\n\"on\": push\n",
+        )
+        .unwrap();
+
+        let mut configuration = Configuration::load(test_project.project()).unwrap();
+        let workflow_configuration = command.update_configuration(&mut configuration);
+        let (workflow, _) = command
+            .render(
+                &configuration,
+                &workflow_configuration,
+                &Lockfile::default(),
+            )
+            .await
+            .unwrap();
+
+        let diff = command.diff(&workflow).unwrap().unwrap();
+
+        assert!(diff.starts_with("--- a/.github/workflows/rust.yml\n"));
+        assert!(diff.contains("-name: Rust\n"));
+        assert!(diff.contains("+name: Rust CI\n"));
+    }
+
+    /// Returns a configuration with a `rust` workflow from a library on GitHub, and a lockfile
+    /// that locks the workflow to a commit of the library
+    fn locked_github_library() -> (Configuration, Lockfile) {
+        let mut configuration = Configuration::builder()
+            .libraries(LibraryConfiguration::GitHub(
+                GitHubConfiguration::builder()
+                    .owner("jdno")
+                    .repository("workflows")
+                    .build(),
+            ))
+            .build();
+        configuration.add_workflow(
+            WorkflowConfiguration::builder()
+                .name("rust")
+                .jobs(Vec::new())
+                .build(),
+        );
+
+        let workflow = Fragment::builder().name("rust").template("".into()).build();
+        let mut lockfile = Lockfile::default();
+        lockfile.add_workflow(
+            LockedWorkflow::new("github jdno/workflows", &workflow, &[])
+                .with_commits(BTreeMap::from([("default".into(), "abc".into())])),
+        );
+
+        (configuration, lockfile)
+    }
+
+    #[test]
+    fn libraries_are_pinned_to_locked_commits() {
+        let project = Project::at(".".into()).unwrap();
+        let (configuration, lockfile) = locked_github_library();
+        let command = Create::new(&project, "rust", &[]);
+
+        let libraries = command
+            .libraries(&configuration, &configuration.workflows()[0], &lockfile)
+            .unwrap();
+
+        assert_eq!(
+            "repository jdno/workflows@abc",
+            libraries.init(&project, true).to_string()
+        );
+    }
+
+    #[test]
+    fn libraries_are_not_pinned_when_refreshed() {
+        let project = Project::at(".".into()).unwrap();
+        let (configuration, lockfile) = locked_github_library();
+        let command = Create::new(&project, "rust", &[]).refresh(true);
+
+        let libraries = command
+            .libraries(&configuration, &configuration.workflows()[0], &lockfile)
+            .unwrap();
+
+        assert_eq!(
+            "repository jdno/workflows",
+            libraries.init(&project, true).to_string()
+        );
+    }
+
+    #[test]
+    fn libraries_are_not_pinned_when_reference_changed() {
+        let project = Project::at(".".into()).unwrap();
+        let (mut configuration, lockfile) = locked_github_library();
+        configuration.remove_workflow("rust");
+        configuration.add_workflow(
+            WorkflowConfiguration::builder()
+                .name("rust")
+                .jobs(Vec::new())
+                .reference("v2")
+                .build(),
+        );
+        let command = Create::new(&project, "rust", &[]);
+
+        let libraries = command
+            .libraries(&configuration, &configuration.workflows()[0], &lockfile)
+            .unwrap();
+
+        assert_eq!(
+            "repository jdno/workflows@v2",
+            libraries.init(&project, true).to_string()
+        );
+    }
+
     #[test]
     fn trait_display() {
         let project = Project::at(".".into()).unwrap();
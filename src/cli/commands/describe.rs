@@ -0,0 +1,222 @@
+use std::fmt::{Display, Formatter, Write};
+
+use anyhow::{Context, Error};
+use async_trait::async_trait;
+
+use crate::cli::{Command, Configuration};
+use crate::{Fragment, Project};
+
+/// Describes a workflow or job in the library with the metadata that its template declares
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Describe<'a> {
+    project: &'a Project,
+    workflow: &'a str,
+    job: Option<&'a str>,
+    offline: bool,
+}
+
+impl<'a> Describe<'a> {
+    pub fn new(project: &'a Project, workflow: &'a str, job: Option<&'a str>) -> Self {
+        Self {
+            project,
+            workflow,
+            job,
+            offline: false,
+        }
+    }
+
+    /// Reads templates only from the cache instead of downloading them
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    async fn fragment(&self) -> Result<Fragment, Error> {
+        let configuration = Configuration::load(self.project)?;
+        let library = configuration.libraries().init(self.project, self.offline);
+
+        match self.job {
+            Some(job) => library.job(self.workflow, job).await.context(format!(
+                "failed to read job '{job}' of workflow '{}'",
+                self.workflow
+            )),
+            None => library
+                .workflow(self.workflow)
+                .await
+                .context(format!("failed to read workflow '{}'", self.workflow)),
+        }
+    }
+
+    async fn describe(&self) -> Result<String, Error> {
+        let fragment = self.fragment().await?;
+        let metadata = fragment.metadata();
+
+        let mut description = String::new();
+
+        match self.job {
+            Some(job) => writeln!(description, "{}/{job}", self.workflow)?,
+            None => writeln!(description, "{}", self.workflow)?,
+        }
+        if let Some(text) = metadata.description() {
+            writeln!(description, "\n{}", text.trim_end())?;
+        }
+
+        let mut details = String::new();
+        if let Some(author) = metadata.author() {
+            writeln!(details, "Author: {author}")?;
+        }
+        if !metadata.workflows().is_empty() {
+            writeln!(details, "Workflows: {}", metadata.workflows().join(", "))?;
+        }
+        if !metadata.requires().is_empty() {
+            writeln!(details, "Requires: {}", metadata.requires().join(", "))?;
+        }
+        if !fragment.variables().is_empty() {
+            writeln!(details, "Variables:")?;
+            for variable in fragment.variables() {
+                match variable.default() {
+                    Some(default) => writeln!(details, "  {variable} (default: {default})")?,
+                    None => writeln!(details, "  {variable} (required)")?,
+                }
+            }
+        }
+        if !metadata.secrets().is_empty() {
+            writeln!(details, "Secrets:")?;
+            for secret in metadata.secrets() {
+                writeln!(details, "  {secret}")?;
+            }
+        }
+
+        if !details.is_empty() {
+            write!(description, "\n{details}")?;
+        }
+
+        Ok(description)
+    }
+}
+
+#[async_trait]
+impl<'a> Command for Describe<'a> {
+    async fn run(&self) -> Result<(), Error> {
+        print!("{}", self.describe().await?);
+
+        Ok(())
+    }
+}
+
+impl Display for Describe<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.job {
+            Some(job) => write!(f, "describe {} {job}", self.workflow),
+            None => write!(f, "describe {}", self.workflow),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::cli::commands::local_project;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn describe_job() {
+        let test_project = local_project();
+
+        std::fs::write(
+            test_project.path().join("templates/rust/lint.meta.yml"),
+            indoc!(
+                r#"
+                description: Lint the code with clippy
+                author: jdno
+                variables:
+                  toolchain: stable
+                  features:
+                secrets:
+                  - CARGO_REGISTRY_TOKEN
+                "#
+            ),
+        )
+        .unwrap();
+
+        let description = Describe::new(test_project.project(), "rust", Some("lint"))
+            .describe()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                rust/lint
+
+                Lint the code with clippy
+
+                Author: jdno
+                Variables:
+                  toolchain (default: stable)
+                  features (required)
+                Secrets:
+                  CARGO_REGISTRY_TOKEN
+                "#
+            ),
+            description
+        );
+    }
+
+    #[tokio::test]
+    async fn describe_workflow_without_metadata() {
+        let test_project = local_project();
+
+        let description = Describe::new(test_project.project(), "rust", None)
+            .describe()
+            .await
+            .unwrap();
+
+        assert_eq!("rust\n", description);
+    }
+
+    #[tokio::test]
+    async fn describe_missing_job() {
+        let test_project = local_project();
+
+        let error = Describe::new(test_project.project(), "rust", Some("test"))
+            .describe()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "failed to read job 'test' of workflow 'rust'",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        let project = Project::at(".".into()).unwrap();
+
+        assert_eq!(
+            "describe rust lint",
+            Describe::new(&project, "rust", Some("lint")).to_string()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Describe>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Describe>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Describe>();
+    }
+}
@@ -3,28 +3,40 @@ use std::fmt::{Display, Formatter};
 use anyhow::{Context, Error};
 use async_trait::async_trait;
 
+use crate::builtin::BuiltinConfiguration;
 use crate::cli::{Command, Configuration, LibraryConfiguration};
 use crate::github::{GitHubConfiguration, Owner, Repository};
 use crate::Project;
 
 const REPO_PARSE_ERROR: &str = "repository must be provided in the format 'owner/repository'";
 
+/// Creates the configuration file for a project
+///
+/// The project uses either a repository on GitHub or the built-in templates as its library.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct Init<'a> {
     project: &'a Project,
-    repository: &'a str,
+    repository: Option<&'a str>,
 }
 
 impl<'a> Init<'a> {
     pub fn new(project: &'a Project, repository: &'a str) -> Self {
         Self {
             project,
-            repository,
+            repository: Some(repository),
         }
     }
 
-    fn parse_repository(self) -> Result<(Owner, Repository), Error> {
-        let mut parts = self.repository.split('/');
+    /// Uses the templates that are compiled into FlowCrafter, which work without network access
+    pub fn builtin(project: &'a Project) -> Self {
+        Self {
+            project,
+            repository: None,
+        }
+    }
+
+    fn parse_repository(repository: &str) -> Result<(Owner, Repository), Error> {
+        let mut parts = repository.split('/');
 
         let owner = parts.next().context(REPO_PARSE_ERROR)?.into();
         let repository = parts.next().context(REPO_PARSE_ERROR)?.into();
@@ -32,14 +44,24 @@ impl<'a> Init<'a> {
         Ok((owner, repository))
     }
 
-    fn create_config(&self, owner: Owner, repository: Repository) -> Result<Configuration, Error> {
+    fn library(&self) -> Result<LibraryConfiguration, Error> {
+        let Some(repository) = self.repository else {
+            return Ok(LibraryConfiguration::Builtin(BuiltinConfiguration::new()));
+        };
+
+        let (owner, repository) = Self::parse_repository(repository)?;
+
+        Ok(LibraryConfiguration::GitHub(
+            GitHubConfiguration::builder()
+                .owner(owner)
+                .repository(repository)
+                .build(),
+        ))
+    }
+
+    fn create_config(&self, library: LibraryConfiguration) -> Result<Configuration, Error> {
         let config = Configuration::builder()
-            .library(LibraryConfiguration::GitHub(
-                GitHubConfiguration::builder()
-                    .owner(owner)
-                    .repository(repository)
-                    .build(),
-            ))
+            .libraries(library)
             .workflows(Vec::new())
             .build();
 
@@ -52,9 +74,7 @@ impl<'a> Init<'a> {
 #[async_trait]
 impl Command for Init<'_> {
     async fn run(&self) -> Result<(), Error> {
-        let (owner, repository) = self.parse_repository()?;
-
-        let _config = self.create_config(owner, repository)?;
+        let _config = self.create_config(self.library()?)?;
 
         Ok(())
     }
@@ -62,7 +82,10 @@ impl Command for Init<'_> {
 
 impl Display for Init<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "init -r {}", self.repository)
+        match self.repository {
+            Some(repository) => write!(f, "init -r {repository}"),
+            None => write!(f, "init --builtin"),
+        }
     }
 }
 
@@ -74,10 +97,7 @@ mod tests {
 
     #[test]
     fn parse_repository() {
-        let project = Project::at(".".into()).unwrap();
-        let command = Init::new(&project, "jdno/flowcrafter");
-
-        let (owner, repository) = command.parse_repository().unwrap();
+        let (owner, repository) = Init::parse_repository("jdno/flowcrafter").unwrap();
 
         assert_eq!(Owner::from("jdno"), owner);
         assert_eq!(Repository::from("flowcrafter"), repository);
@@ -85,10 +105,7 @@ mod tests {
 
     #[test]
     fn parse_repository_with_invalid_format() {
-        let project = Project::at(".".into()).unwrap();
-        let command = Init::new(&project, "flowcrafter");
-
-        let result = command.parse_repository().unwrap_err();
+        let result = Init::parse_repository("flowcrafter").unwrap_err();
 
         assert!(result.to_string().contains(REPO_PARSE_ERROR));
     }
@@ -162,6 +179,27 @@ mod tests {
         assert!(contents.contains("repository: flowcrafter"));
     }
 
+    #[tokio::test]
+    async fn run_writes_builtin_config() {
+        let test_project = TestProject::new().unwrap();
+
+        Init::builtin(test_project.project()).run().await.unwrap();
+
+        let configuration = Configuration::load(test_project.project()).unwrap();
+
+        assert_eq!(
+            Some(&LibraryConfiguration::Builtin(BuiltinConfiguration::new())),
+            configuration.libraries().default_library()
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        let project = Project::at(".".into()).unwrap();
+
+        assert_eq!("init --builtin", Init::builtin(&project).to_string());
+    }
+
     #[test]
     fn trait_send() {
         fn assert_send<T: Send>() {}
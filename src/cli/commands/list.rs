@@ -0,0 +1,209 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::{Context, Error};
+use async_trait::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
+
+use crate::cli::commands::MAX_CONCURRENT_DOWNLOADS;
+use crate::cli::{Command, Configuration};
+use crate::{FragmentLibrary, Project};
+
+/// Lists the workflows in the library, or the jobs of a workflow
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct List<'a> {
+    project: &'a Project,
+    workflow: Option<&'a str>,
+    long: bool,
+    offline: bool,
+}
+
+impl<'a> List<'a> {
+    pub fn new(project: &'a Project, workflow: Option<&'a str>) -> Self {
+        Self {
+            project,
+            workflow,
+            long: false,
+            offline: false,
+        }
+    }
+
+    /// Prints the description of each workflow or job next to its name
+    pub fn long(mut self, long: bool) -> Self {
+        self.long = long;
+        self
+    }
+
+    /// Reads templates only from the cache instead of downloading them
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    async fn lines(&self) -> Result<Vec<String>, Error> {
+        let configuration = Configuration::load(self.project)?;
+        let library = configuration.libraries().init(self.project, self.offline);
+
+        let names = match self.workflow {
+            Some(workflow) => library
+                .jobs(workflow)
+                .await
+                .context(format!("failed to list jobs for workflow '{workflow}'"))?,
+            None => library
+                .workflows()
+                .await
+                .context("failed to list workflows")?,
+        };
+
+        if !self.long {
+            return Ok(names);
+        }
+
+        let width = names.iter().map(String::len).max().unwrap_or_default();
+        let downloads: Vec<_> = names
+            .iter()
+            .map(|name| self.description(library.as_ref(), name))
+            .collect();
+        let descriptions: Vec<Option<String>> = stream::iter(downloads)
+            .buffered(MAX_CONCURRENT_DOWNLOADS)
+            .try_collect()
+            .await?;
+
+        Ok(names
+            .iter()
+            .zip(descriptions)
+            .map(|(name, description)| match description {
+                Some(description) => format!("{name:width$}  {description}"),
+                None => name.clone(),
+            })
+            .collect())
+    }
+
+    /// Returns the first line of the description of a workflow or job
+    async fn description(
+        &self,
+        library: &dyn for<'b> FragmentLibrary<'b>,
+        name: &str,
+    ) -> Result<Option<String>, Error> {
+        let fragment = match self.workflow {
+            Some(workflow) => library.job(workflow, name).await,
+            None => library.workflow(name).await,
+        }
+        .context(format!("failed to read '{name}'"))?;
+
+        Ok(fragment
+            .metadata()
+            .description()
+            .and_then(|description| description.lines().next())
+            .map(String::from))
+    }
+}
+
+#[async_trait]
+impl<'a> Command for List<'a> {
+    async fn run(&self) -> Result<(), Error> {
+        for line in self.lines().await? {
+            println!("{line}");
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for List<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.workflow {
+            Some(workflow) => write!(f, "list {workflow}"),
+            None => write!(f, "list"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::commands::local_project;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn names_of_workflows() {
+        let test_project = local_project();
+
+        let names = List::new(test_project.project(), None)
+            .lines()
+            .await
+            .unwrap();
+
+        assert_eq!(vec!["rust"], names);
+    }
+
+    #[tokio::test]
+    async fn names_of_jobs() {
+        let test_project = local_project();
+
+        let names = List::new(test_project.project(), Some("rust"))
+            .lines()
+            .await
+            .unwrap();
+
+        assert_eq!(vec!["lint"], names);
+    }
+
+    #[tokio::test]
+    async fn lines_with_descriptions() {
+        let test_project = local_project();
+
+        std::fs::write(
+            test_project.path().join("templates/rust/style.yml"),
+            "---\ndescription: Check the formatting\n---\nstyle:\n  runs-on: ubuntu-latest\n",
+        )
+        .unwrap();
+
+        let lines = List::new(test_project.project(), Some("rust"))
+            .long(true)
+            .lines()
+            .await
+            .unwrap();
+
+        assert_eq!(vec!["lint", "style  Check the formatting"], lines);
+    }
+
+    #[tokio::test]
+    async fn names_of_jobs_for_missing_workflow() {
+        let test_project = local_project();
+
+        let error = List::new(test_project.project(), Some("python"))
+            .lines()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "failed to list jobs for workflow 'python'",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        let project = Project::at(".".into()).unwrap();
+
+        assert_eq!("list rust", List::new(&project, Some("rust")).to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<List>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<List>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<List>();
+    }
+}
@@ -1,16 +1,157 @@
-use anyhow::Error;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Error};
 use async_trait::async_trait;
 use clap::Subcommand;
+use futures::{stream, StreamExt};
 
 use crate::cli::commands::update::Update;
-use crate::Project;
+use crate::cli::diff::unified_diff;
+use crate::cli::{Configuration, Libraries, LockedWorkflow, Lockfile};
+use crate::{Project, Workflow};
 
+pub use self::add::Add;
+pub use self::check::Check;
 pub use self::create::Create;
+pub use self::describe::Describe;
 pub use self::init::Init;
+pub use self::list::List;
+pub use self::remove::Remove;
+pub use self::validate::Validate;
+pub use self::wizard::{Prompt, Terminal, Wizard};
 
+mod add;
+mod check;
 mod create;
+mod describe;
 mod init;
+mod list;
+mod remove;
 mod update;
+mod validate;
+mod wizard;
+
+/// The maximum number of workflows, jobs, or steps that are processed at the same time
+///
+/// The limit applies separately to each level, e.g. to the jobs of each workflow that is rendered,
+/// so it does not bound the total number of downloads. Libraries that download fragments over the
+/// network limit their requests themselves.
+const MAX_CONCURRENT_DOWNLOADS: usize = 8;
+
+/// Returns the name of each workflow in the configuration with the keys of its jobs
+///
+/// The keys are owned so that the commands from [`create_commands`] can borrow them.
+fn configured_workflows(configuration: &Configuration) -> Vec<(&str, Vec<String>)> {
+    configuration
+        .workflows()
+        .iter()
+        .map(|workflow| {
+            let jobs = workflow
+                .jobs()
+                .iter()
+                .map(|job| job.key().to_string())
+                .collect();
+
+            (workflow.name(), jobs)
+        })
+        .collect()
+}
+
+/// Creates a command for each workflow, in the order of the configuration
+fn create_commands<'a>(
+    project: &'a Project,
+    workflows: &'a [(&'a str, Vec<String>)],
+) -> impl Iterator<Item = Create<'a>> {
+    workflows
+        .iter()
+        .map(move |(workflow, jobs)| Create::new(project, workflow, jobs))
+}
+
+/// Renders the workflows of the configuration concurrently, with a command for each workflow
+///
+/// Workflows that use the same library share it, so that its client is only created once. The
+/// result of each workflow is returned in the order of the commands, so that one workflow that
+/// fails to render does not hide the others.
+async fn render_workflows(
+    project: &Project,
+    configuration: &Configuration,
+    commands: &[Create<'_>],
+    lockfile: &Lockfile,
+    offline: bool,
+) -> Vec<Result<(Workflow, LockedWorkflow), Error>> {
+    let mut libraries: Vec<(Libraries, Box<_>)> = Vec::new();
+    let mut renders = Vec::new();
+
+    for (command, workflow_configuration) in commands.iter().zip(configuration.workflows()) {
+        let workflow_libraries = command.libraries(configuration, workflow_configuration, lockfile);
+
+        if let Ok(workflow_libraries) = &workflow_libraries {
+            if !libraries
+                .iter()
+                .any(|(existing, _)| existing == workflow_libraries)
+            {
+                let library = workflow_libraries.init(project, offline);
+                libraries.push((workflow_libraries.clone(), library));
+            }
+        }
+
+        renders.push((command, workflow_libraries, workflow_configuration));
+    }
+
+    let renders: Vec<_> = renders
+        .into_iter()
+        .map(|(command, workflow_libraries, workflow_configuration)| {
+            let libraries = &libraries;
+
+            async move {
+                let workflow_libraries = workflow_libraries?;
+                let (_, library) = libraries
+                    .iter()
+                    .find(|(existing, _)| existing == &workflow_libraries)
+                    .expect("library has been initialized");
+
+                command
+                    .render_from(library.deref(), &workflow_libraries, workflow_configuration)
+                    .await
+            }
+        })
+        .collect();
+
+    stream::iter(renders)
+        .buffered(MAX_CONCURRENT_DOWNLOADS)
+        .collect()
+        .await
+}
+
+/// Prints the changes that would be written to the configuration in dry-run mode
+fn print_configuration_diff(project: &Project, configuration: &Configuration) -> Result<(), Error> {
+    let path = Configuration::path(project);
+
+    if let Some(diff) = diff_file(project, &path, &configuration.to_yaml()?)? {
+        print!("{diff}");
+    }
+
+    Ok(())
+}
+
+/// Returns a unified diff between a file in the project and its new contents
+fn diff_file(project: &Project, path: &Path, new: &str) -> Result<Option<String>, Error> {
+    let relative_path = path.strip_prefix(project.path()).unwrap_or(path);
+
+    let current = if path.exists() {
+        std::fs::read_to_string(path)
+            .context(format!("failed to read {}", relative_path.display()))?
+    } else {
+        String::new()
+    };
+
+    Ok(unified_diff(
+        &relative_path.display().to_string(),
+        &current,
+        new,
+    ))
+}
 
 #[async_trait]
 pub trait Command {
@@ -19,25 +160,202 @@ pub trait Command {
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Subcommand)]
 pub enum Commands {
-    Create {
+    /// Add jobs to a workflow that has already been created
+    Add {
         #[arg(short, long)]
         workflow: String,
+        #[arg(short, long, required = true)]
+        jobs: Vec<String>,
+        /// Print the changes as a diff instead of writing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Overwrite workflows that have been modified by hand
+        #[arg(long)]
+        force: bool,
+    },
+    /// Check that the generated workflows match the configuration
+    Check,
+    /// Create a workflow, or pick the workflow and its jobs interactively if they are not given
+    Create {
         #[arg(short, long)]
+        workflow: Option<String>,
+        #[arg(short, long, requires = "workflow")]
         jobs: Vec<String>,
+        /// Print the changes as a diff instead of writing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Overwrite workflows that have been modified by hand
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show the description, variables, and secrets of a workflow or job
+    Describe {
+        workflow: String,
+        job: Option<String>,
     },
+    /// Initialize FlowCrafter with a template repository or the built-in templates
     Init {
+        #[arg(short, long, required_unless_present = "builtin")]
+        repository: Option<String>,
+        /// Use the templates that are built into FlowCrafter
+        #[arg(long, conflicts_with = "repository")]
+        builtin: bool,
+    },
+    /// List the workflows in the library, or the jobs of a workflow
+    List {
+        workflow: Option<String>,
+        /// Print the description of each workflow or job next to its name
         #[arg(short, long)]
-        repository: String,
+        long: bool,
     },
-    Update,
+    /// Remove a workflow, or remove jobs from a workflow
+    Remove {
+        #[arg(short, long)]
+        workflow: String,
+        #[arg(short, long)]
+        jobs: Vec<String>,
+        /// Remove workflows that have been modified by hand
+        #[arg(long)]
+        force: bool,
+    },
+    Update {
+        /// Fail if the templates differ from the revisions in the lockfile
+        #[arg(long)]
+        locked: bool,
+        /// Render from the latest commits of the libraries instead of the commits in the lockfile
+        #[arg(long, conflicts_with = "locked")]
+        refresh: bool,
+        /// Print the changes as a diff instead of writing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Overwrite workflows that have been modified by hand
+        #[arg(long)]
+        force: bool,
+    },
+    /// Validate workflow files, or all workflows in .github/workflows
+    Validate { files: Vec<PathBuf> },
 }
 
 impl Commands {
-    pub async fn execute(command: &Commands, project: &Project) -> Result<(), Error> {
+    /// Executes the command, with `offline` restricting remote libraries to their cache
+    pub async fn execute(
+        command: &Commands,
+        project: &Project,
+        offline: bool,
+    ) -> Result<(), Error> {
         match command {
-            Commands::Create { workflow, jobs } => Create::new(project, workflow, jobs).run().await,
-            Commands::Init { repository } => Init::new(project, repository).run().await,
-            Commands::Update => Update::new(project).run().await,
+            Commands::Add {
+                workflow,
+                jobs,
+                dry_run,
+                force,
+            } => {
+                Add::new(project, workflow, jobs)
+                    .dry_run(*dry_run)
+                    .force(*force)
+                    .offline(offline)
+                    .run()
+                    .await
+            }
+            Commands::Check => Check::new(project).offline(offline).run().await,
+            Commands::Create {
+                workflow,
+                jobs,
+                dry_run,
+                force,
+            } => match workflow {
+                Some(workflow) if !jobs.is_empty() || !Wizard::is_interactive() => {
+                    Create::new(project, workflow, jobs)
+                        .dry_run(*dry_run)
+                        .force(*force)
+                        .offline(offline)
+                        .run()
+                        .await
+                }
+                workflow => {
+                    Wizard::new(project)
+                        .workflow(workflow.as_deref())
+                        .dry_run(*dry_run)
+                        .force(*force)
+                        .offline(offline)
+                        .run()
+                        .await
+                }
+            },
+            Commands::Describe { workflow, job } => {
+                Describe::new(project, workflow, job.as_deref())
+                    .offline(offline)
+                    .run()
+                    .await
+            }
+            Commands::Init { repository, .. } => match repository {
+                Some(repository) => Init::new(project, repository).run().await,
+                None => Init::builtin(project).run().await,
+            },
+            Commands::List { workflow, long } => {
+                List::new(project, workflow.as_deref())
+                    .long(*long)
+                    .offline(offline)
+                    .run()
+                    .await
+            }
+            Commands::Remove {
+                workflow,
+                jobs,
+                force,
+            } => {
+                Remove::new(project, workflow, jobs)
+                    .force(*force)
+                    .offline(offline)
+                    .run()
+                    .await
+            }
+            Commands::Update {
+                locked,
+                refresh,
+                dry_run,
+                force,
+            } => {
+                Update::new(project)
+                    .locked(*locked)
+                    .refresh(*refresh)
+                    .dry_run(*dry_run)
+                    .force(*force)
+                    .offline(offline)
+                    .run()
+                    .await
+            }
+            Commands::Validate { files } => Validate::new(project, files).run().await,
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) fn local_project() -> crate::TestProject {
+    use crate::cli::{Configuration, LibraryConfiguration};
+    use crate::local::LocalConfiguration;
+
+    let test_project = crate::TestProject::new().unwrap();
+
+    let templates = test_project.path().join("templates").join("rust");
+    std::fs::create_dir_all(&templates).unwrap();
+    std::fs::create_dir_all(test_project.path().join(".github").join("workflows")).unwrap();
+
+    std::fs::write(templates.join("workflow.yml"), "name: Rust\n\"on\": push\n").unwrap();
+    std::fs::write(
+        templates.join("lint.yml"),
+        "lint:\n  runs-on: ubuntu-latest\n",
+    )
+    .unwrap();
+
+    Configuration::builder()
+        .libraries(LibraryConfiguration::Local(
+            LocalConfiguration::builder().path("templates").build(),
+        ))
+        .workflows(Vec::new())
+        .build()
+        .save(test_project.project())
+        .unwrap();
+
+    test_project
+}
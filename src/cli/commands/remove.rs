@@ -0,0 +1,307 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::{anyhow, Context, Error};
+use async_trait::async_trait;
+
+use crate::cli::configuration::WorkflowConfiguration;
+use crate::cli::{Command, Configuration, Create, Lockfile};
+use crate::Project;
+
+/// Removes a workflow, or removes jobs from a workflow and renders it again
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Remove<'a> {
+    project: &'a Project,
+    workflow: &'a str,
+    jobs: &'a [String],
+    force: bool,
+    offline: bool,
+}
+
+impl<'a> Remove<'a> {
+    pub fn new(project: &'a Project, workflow: &'a str, jobs: &'a [String]) -> Self {
+        Self {
+            project,
+            workflow,
+            jobs,
+            force: false,
+            offline: false,
+        }
+    }
+
+    /// Removes the workflow even if it has been modified by hand
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Reads templates only from the cache instead of downloading them
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    fn remove_workflow(&self, mut configuration: Configuration) -> Result<(), Error> {
+        let command = Create::new(self.project, self.workflow, &[]);
+
+        if !self.force && command.is_modified()? {
+            return Err(anyhow!(
+                "{} has been modified by hand, use --force to remove it",
+                command.relative_workflow_path().display()
+            ));
+        }
+
+        let path = command.workflow_path();
+        if path.exists() {
+            std::fs::remove_file(path).context("failed to remove workflow file")?;
+        }
+
+        // Only write the lockfile if it records the workflow, so that removing never creates one
+        let mut lockfile = Lockfile::load(self.project)?;
+        if lockfile.workflow(command.workflow_name()).is_some() {
+            lockfile.remove_workflow(command.workflow_name());
+            lockfile.save(self.project)?;
+        }
+
+        configuration.remove_workflow(self.workflow);
+        configuration.save(self.project)
+    }
+
+    async fn remove_jobs(&self, workflow: &WorkflowConfiguration) -> Result<(), Error> {
+        for job in self.jobs {
+            if !workflow.jobs().iter().any(|existing| existing.key() == job) {
+                return Err(anyhow!(
+                    "job '{job}' is not part of workflow '{}'",
+                    self.workflow
+                ));
+            }
+        }
+
+        let remaining_jobs: Vec<String> = workflow
+            .jobs()
+            .iter()
+            .map(|job| job.key().to_string())
+            .filter(|job| !self.jobs.contains(job))
+            .collect();
+
+        Create::new(self.project, self.workflow, &remaining_jobs)
+            .force(self.force)
+            .offline(self.offline)
+            .run()
+            .await
+    }
+}
+
+#[async_trait]
+impl<'a> Command for Remove<'a> {
+    async fn run(&self) -> Result<(), Error> {
+        let configuration = Configuration::load(self.project)?;
+
+        let Some(workflow) = configuration
+            .workflows()
+            .iter()
+            .find(|workflow| workflow.name() == self.workflow)
+            .cloned()
+        else {
+            return Err(anyhow!("workflow '{}' is not configured", self.workflow));
+        };
+
+        if self.jobs.is_empty() {
+            self.remove_workflow(configuration)
+        } else {
+            self.remove_jobs(&workflow).await
+        }
+    }
+}
+
+impl Display for Remove<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "remove -w {}", self.workflow)?;
+
+        for job in self.jobs {
+            write!(f, " -j {job}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::commands::local_project;
+
+    use super::*;
+
+    async fn create_workflow(project: &Project) {
+        std::fs::write(
+            project.path().join("templates/rust/test.yml"),
+            "test:\n  runs-on: ubuntu-latest\n",
+        )
+        .unwrap();
+
+        let jobs = vec!["lint".into(), "test".into()];
+        Create::new(project, "rust", &jobs).run().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn run_removes_workflow() {
+        let test_project = local_project();
+        create_workflow(test_project.project()).await;
+
+        Remove::new(test_project.project(), "rust", &[])
+            .run()
+            .await
+            .unwrap();
+
+        let configuration = Configuration::load(test_project.project()).unwrap();
+        let lockfile = Lockfile::load(test_project.project()).unwrap();
+
+        assert!(!test_project
+            .path()
+            .join(".github/workflows/rust.yml")
+            .exists());
+        assert!(configuration.workflows().is_empty());
+        assert!(lockfile.workflow("rust").is_none());
+    }
+
+    #[tokio::test]
+    async fn run_removes_workflow_without_creating_lockfile() {
+        let test_project = local_project();
+        create_workflow(test_project.project()).await;
+
+        let lockfile_path = test_project.path().join(".github/flowcrafter.lock");
+        std::fs::remove_file(&lockfile_path).unwrap();
+
+        Remove::new(test_project.project(), "rust", &[])
+            .run()
+            .await
+            .unwrap();
+
+        assert!(!lockfile_path.exists());
+    }
+
+    #[tokio::test]
+    async fn run_errors_when_removing_last_job() {
+        let test_project = local_project();
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        let error = Remove::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap_err();
+        let configuration = Configuration::load(test_project.project()).unwrap();
+
+        assert_eq!(
+            "workflow 'rust' would have no jobs; add jobs with -j, or use `remove -w rust` to \
+             remove it",
+            error.to_string()
+        );
+        assert_eq!(1, configuration.workflows()[0].jobs().len());
+    }
+
+    #[tokio::test]
+    async fn run_removes_jobs() {
+        let test_project = local_project();
+        create_workflow(test_project.project()).await;
+
+        let jobs = vec!["test".into()];
+        Remove::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        let configuration = Configuration::load(test_project.project()).unwrap();
+        let workflow =
+            std::fs::read_to_string(test_project.path().join(".github/workflows/rust.yml"))
+                .unwrap();
+
+        assert_eq!(1, configuration.workflows()[0].jobs().len());
+        assert_eq!("lint", configuration.workflows()[0].jobs()[0].name());
+        assert!(workflow.contains("lint:"));
+        assert!(!workflow.contains("test:"));
+    }
+
+    #[tokio::test]
+    async fn run_errors_for_unknown_job() {
+        let test_project = local_project();
+        create_workflow(test_project.project()).await;
+
+        let jobs = vec!["style".into()];
+        let error = Remove::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "job 'style' is not part of workflow 'rust'",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_errors_for_unknown_workflow() {
+        let test_project = local_project();
+
+        let error = Remove::new(test_project.project(), "python", &[])
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!("workflow 'python' is not configured", error.to_string());
+    }
+
+    #[tokio::test]
+    async fn run_errors_when_workflow_modified_by_hand() {
+        let test_project = local_project();
+        create_workflow(test_project.project()).await;
+
+        let workflow_path = test_project.path().join(".github/workflows/rust.yml");
+        let contents = std::fs::read_to_string(&workflow_path).unwrap();
+        std::fs::write(&workflow_path, contents.replace("Rust", "Edited by hand")).unwrap();
+
+        let error = Remove::new(test_project.project(), "rust", &[])
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            ".github/workflows/rust.yml has been modified by hand, use --force to remove it",
+            error.to_string()
+        );
+        assert!(workflow_path.exists());
+    }
+
+    #[test]
+    fn trait_display() {
+        let project = Project::at(".".into()).unwrap();
+
+        let jobs = vec!["lint".into()];
+
+        assert_eq!(
+            "remove -w rust -j lint",
+            Remove::new(&project, "rust", &jobs).to_string()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Remove>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Remove>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Remove>();
+    }
+}
@@ -1,16 +1,81 @@
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use async_trait::async_trait;
 
-use crate::cli::{Command, Configuration, Create};
+use crate::cli::commands::{
+    configured_workflows, create_commands, print_configuration_diff, render_workflows,
+};
+use crate::cli::{Command, Configuration, Create, Lockfile};
 use crate::Project;
 
 pub struct Update<'a> {
     project: &'a Project,
+    locked: bool,
+    refresh: bool,
+    dry_run: bool,
+    force: bool,
+    offline: bool,
 }
 
 impl<'a> Update<'a> {
     pub fn new(project: &'a Project) -> Self {
-        Self { project }
+        Self {
+            project,
+            locked: false,
+            refresh: false,
+            dry_run: false,
+            force: false,
+            offline: false,
+        }
+    }
+
+    /// Fails when the templates differ from the revisions in the lockfile
+    pub fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Renders from the latest commits of the libraries instead of the commits in the lockfile
+    pub fn refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Prints the changes to the workflows instead of writing them to disk
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Overwrites workflows even if they have been modified by hand
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Reads templates only from the cache instead of downloading them
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    fn check_modified(&self, commands: &[Create]) -> Result<(), Error> {
+        let mut modified = Vec::new();
+
+        for command in commands {
+            if command.is_modified()? {
+                modified.push(command.relative_workflow_path().display().to_string());
+            }
+        }
+
+        if modified.is_empty() {
+            return Ok(());
+        }
+
+        Err(anyhow!(
+            "{} workflow(s) have been modified by hand, use --force to overwrite them:\n  {}",
+            modified.len(),
+            modified.join("\n  ")
+        ))
     }
 }
 
@@ -19,19 +84,207 @@ impl<'a> Command for Update<'a> {
     async fn run(&self) -> Result<(), Error> {
         let configuration = Configuration::load(self.project)?;
 
-        for workflow in configuration.workflows() {
-            let command = Create::new(self.project, workflow.name(), workflow.jobs());
-            command.run().await?;
+        let workflows = configured_workflows(&configuration);
+
+        let commands: Vec<Create> = create_commands(self.project, &workflows)
+            .map(|command| {
+                command
+                    .locked(self.locked)
+                    .refresh(self.refresh)
+                    .dry_run(self.dry_run)
+                    .force(self.force)
+                    .offline(self.offline)
+            })
+            .collect();
+
+        if !self.force && !self.dry_run {
+            self.check_modified(&commands)?;
+        }
+
+        let mut lockfile = Lockfile::load(self.project)?;
+        let (rendered_workflows, locked_workflows): (Vec<_>, Vec<_>) = render_workflows(
+            self.project,
+            &configuration,
+            &commands,
+            &lockfile,
+            self.offline,
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, Error>>()?
+        .into_iter()
+        .unzip();
+
+        for (command, locked_workflow) in commands.iter().zip(locked_workflows) {
+            command.update_lockfile(locked_workflow, &mut lockfile)?;
+        }
+
+        for (command, workflow) in commands.iter().zip(&rendered_workflows) {
+            command.write(workflow)?;
         }
 
-        Ok(())
+        if self.dry_run {
+            return print_configuration_diff(self.project, &configuration);
+        }
+        if !self.locked {
+            lockfile.save(self.project)?;
+        }
+
+        configuration.save(self.project)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::cli::commands::local_project;
+
     use super::*;
 
+    #[tokio::test]
+    async fn run_updates_all_workflows() {
+        let test_project = local_project();
+
+        let templates = test_project.path().join("templates/markdown");
+        std::fs::create_dir_all(&templates).unwrap();
+        std::fs::write(
+            templates.join("workflow.yml"),
+            "name: Markdown\n\"on\": push\n",
+        )
+        .unwrap();
+        std::fs::write(
+            templates.join("style.yml"),
+            "style:\n  runs-on: ubuntu-latest\n",
+        )
+        .unwrap();
+
+        for (workflow, job) in [("rust", "lint"), ("markdown", "style")] {
+            let jobs = vec![job.into()];
+            Create::new(test_project.project(), workflow, &jobs)
+                .run()
+                .await
+                .unwrap();
+        }
+
+        std::fs::write(
+            test_project.path().join("templates/rust/lint.yml"),
+            "lint:\n  runs-on: macos-latest\n",
+        )
+        .unwrap();
+        std::fs::write(templates.join("workflow.yml"), "name: Docs\n\"on\": push\n").unwrap();
+
+        Update::new(test_project.project()).run().await.unwrap();
+
+        let rust = std::fs::read_to_string(test_project.path().join(".github/workflows/rust.yml"))
+            .unwrap();
+        let markdown =
+            std::fs::read_to_string(test_project.path().join(".github/workflows/markdown.yml"))
+                .unwrap();
+        let lockfile = Lockfile::load(test_project.project()).unwrap();
+
+        assert!(rust.contains("runs-on: macos-latest"));
+        assert!(markdown.contains("name: Docs"));
+        assert_eq!(2, lockfile.workflows().len());
+    }
+
+    #[tokio::test]
+    async fn run_renders_job_aliases() {
+        let test_project = local_project();
+
+        std::fs::write(
+            test_project.path().join("templates/rust/lint.yml"),
+            "---\nvariables:\n  crate:\n---\nlint:\n  name: Lint {{ crate }}\n  runs-on: ubuntu-latest\n",
+        )
+        .unwrap();
+        std::fs::write(
+            Configuration::path(test_project.project()),
+            indoc::indoc!(
+                r#"
+                library:
+                  local:
+                    path: templates
+                workflows:
+                  - name: rust
+                    jobs:
+                      - name: lint
+                        id: lint-core
+                        variables:
+                          crate: core
+                      - name: lint
+                        id: lint-cli
+                        variables:
+                          crate: cli
+                "#
+            ),
+        )
+        .unwrap();
+
+        Update::new(test_project.project()).run().await.unwrap();
+
+        let workflow =
+            std::fs::read_to_string(test_project.path().join(".github/workflows/rust.yml"))
+                .unwrap();
+        let lockfile = Lockfile::load(test_project.project()).unwrap();
+
+        assert!(workflow.ends_with(indoc::indoc!(
+            r#"
+            jobs:
+              lint-core:
+                name: Lint core
+                runs-on: ubuntu-latest
+
+              lint-cli:
+                name: Lint cli
+                runs-on: ubuntu-latest
+            "#
+        )));
+        assert_eq!(1, lockfile.workflow("rust").unwrap().jobs().len());
+    }
+
+    #[tokio::test]
+    async fn run_reports_workflows_modified_by_hand() {
+        let test_project = local_project();
+        let workflow_path = test_project.path().join(".github/workflows/rust.yml");
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&workflow_path).unwrap();
+        std::fs::write(&workflow_path, contents.replace("Rust", "Edited by hand")).unwrap();
+
+        let error = Update::new(test_project.project()).run().await.unwrap_err();
+
+        assert_eq!(
+            "1 workflow(s) have been modified by hand, use --force to overwrite them:\n  .github/workflows/rust.yml",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_force_overwrites_workflows_modified_by_hand() {
+        let test_project = local_project();
+        let workflow_path = test_project.path().join(".github/workflows/rust.yml");
+
+        let jobs = vec!["lint".into()];
+        Create::new(test_project.project(), "rust", &jobs)
+            .run()
+            .await
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&workflow_path).unwrap();
+        std::fs::write(&workflow_path, contents.replace("Rust", "Edited by hand")).unwrap();
+
+        Update::new(test_project.project())
+            .force(true)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(contents, std::fs::read_to_string(&workflow_path).unwrap());
+    }
+
     #[test]
     fn trait_send() {
         fn assert_send<T: Send>() {}
@@ -0,0 +1,180 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Error};
+use async_trait::async_trait;
+
+use crate::cli::Command;
+use crate::{validate, Error as CrateError, Project};
+
+/// Validates workflow files against the schema for GitHub Actions workflows
+///
+/// Without any files, all workflows in `.github/workflows` are validated, including the ones that
+/// are not managed by FlowCrafter.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Validate<'a> {
+    project: &'a Project,
+    files: &'a [PathBuf],
+}
+
+impl<'a> Validate<'a> {
+    pub fn new(project: &'a Project, files: &'a [PathBuf]) -> Self {
+        Self { project, files }
+    }
+
+    /// Returns the files that have been given, or the workflows of the project
+    fn files(&self) -> Result<Vec<PathBuf>, Error> {
+        if !self.files.is_empty() {
+            return Ok(self.files.to_vec());
+        }
+
+        let directory = self.project.path().join(".github").join("workflows");
+        let mut files = Vec::new();
+
+        for entry in std::fs::read_dir(&directory).context("failed to read .github/workflows")? {
+            let path = entry?.path();
+
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "yml" || extension == "yaml")
+            {
+                files.push(path);
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+
+    /// Returns the errors of all files that are not valid workflows
+    fn errors(&self) -> Result<Vec<String>, Error> {
+        let mut errors = Vec::new();
+
+        for path in self.files()? {
+            let name = path
+                .strip_prefix(self.project.path())
+                .unwrap_or(&path)
+                .display()
+                .to_string();
+            let source =
+                std::fs::read_to_string(&path).context(format!("failed to read {name}"))?;
+
+            match validate(&name, &source) {
+                Ok(warnings) => {
+                    for warning in warnings {
+                        eprintln!("warning: {name}: {warning}");
+                    }
+                }
+                Err(error @ CrateError::Validation(_, _)) => errors.push(error.to_string()),
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        Ok(errors)
+    }
+}
+
+#[async_trait]
+impl<'a> Command for Validate<'a> {
+    async fn run(&self) -> Result<(), Error> {
+        let errors = self.errors()?;
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        Err(anyhow!("{}", errors.join("\n")))
+    }
+}
+
+impl Display for Validate<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "validate")?;
+
+        for file in self.files {
+            write!(f, " {}", file.display())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::commands::local_project;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn run_validates_workflows_of_project() {
+        let test_project = local_project();
+        let workflows = test_project.path().join(".github/workflows");
+
+        std::fs::write(
+            workflows.join("valid.yml"),
+            "\"on\": push\njobs:\n  test:\n    runs-on: ubuntu-latest\n    steps:\n      - run: make\n",
+        )
+        .unwrap();
+        std::fs::write(workflows.join("invalid.yaml"), "name: Invalid\n").unwrap();
+        std::fs::write(workflows.join("README.md"), "# Workflows\n").unwrap();
+
+        let error = Validate::new(test_project.project(), &[])
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            ".github/workflows/invalid.yaml is not a valid workflow:\n  \
+             missing required key 'on'\n  missing required key 'jobs'",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_validates_given_files() {
+        let test_project = local_project();
+        let file = test_project.path().join("workflow.yml");
+
+        std::fs::write(&file, "\"on\": push\njobs: {}\n").unwrap();
+
+        let files = vec![file];
+        let error = Validate::new(test_project.project(), &files)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "workflow.yml is not a valid workflow:\n  line 2: jobs: must contain at least one job",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        let project = Project::at(".".into()).unwrap();
+        let files = vec![PathBuf::from("ci.yml")];
+
+        assert_eq!(
+            "validate ci.yml",
+            Validate::new(&project, &files).to_string()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Validate>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Validate>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Validate>();
+    }
+}
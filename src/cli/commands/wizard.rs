@@ -0,0 +1,493 @@
+use std::fmt::{Display, Formatter};
+use std::io::IsTerminal;
+
+use anyhow::{anyhow, Context, Error};
+use async_trait::async_trait;
+use dialoguer::{Confirm, MultiSelect, Select};
+
+use crate::cli::configuration::JobConfiguration;
+use crate::cli::{Command, Configuration, Create};
+use crate::Project;
+
+/// Asks the user for input on behalf of the wizard
+pub trait Prompt: Send + Sync {
+    /// Checks if the user can be prompted for input
+    fn is_interactive(&self) -> bool;
+
+    /// Asks the user to pick one of the items, and returns its index
+    fn select(&self, prompt: &str, items: &[String]) -> Result<usize, Error>;
+
+    /// Asks the user to pick any number of the items, and returns their indices
+    fn multi_select(
+        &self,
+        prompt: &str,
+        items: &[String],
+        defaults: &[bool],
+    ) -> Result<Vec<usize>, Error>;
+
+    /// Asks the user a yes or no question
+    fn confirm(&self, prompt: &str) -> Result<bool, Error>;
+}
+
+/// Prompts the user in the terminal
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Terminal;
+
+impl Prompt for Terminal {
+    fn is_interactive(&self) -> bool {
+        std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+    }
+
+    fn select(&self, prompt: &str, items: &[String]) -> Result<usize, Error> {
+        Ok(Select::new()
+            .with_prompt(prompt)
+            .items(items)
+            .default(0)
+            .interact()?)
+    }
+
+    fn multi_select(
+        &self,
+        prompt: &str,
+        items: &[String],
+        defaults: &[bool],
+    ) -> Result<Vec<usize>, Error> {
+        Ok(MultiSelect::new()
+            .with_prompt(prompt)
+            .items(items)
+            .defaults(defaults)
+            .interact()?)
+    }
+
+    fn confirm(&self, prompt: &str) -> Result<bool, Error> {
+        Ok(Confirm::new()
+            .with_prompt(prompt)
+            .default(true)
+            .interact()?)
+    }
+}
+
+/// Creates a workflow by prompting the user for the workflow and its jobs
+///
+/// The wizard lists the workflows and jobs in the library, previews the rendered workflow, and asks
+/// for confirmation before the workflow and the configuration are written. When the workflow has
+/// already been given, the wizard only asks for its jobs.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Wizard<'a, P = Terminal> {
+    project: &'a Project,
+    workflow: Option<&'a str>,
+    prompt: P,
+    dry_run: bool,
+    force: bool,
+    offline: bool,
+}
+
+impl<'a> Wizard<'a> {
+    pub fn new(project: &'a Project) -> Self {
+        Self {
+            project,
+            workflow: None,
+            prompt: Terminal,
+            dry_run: false,
+            force: false,
+            offline: false,
+        }
+    }
+
+    /// Checks if the user can be prompted for input in the terminal
+    pub fn is_interactive() -> bool {
+        Terminal.is_interactive()
+    }
+}
+
+impl<'a, P: Prompt> Wizard<'a, P> {
+    /// Creates the given workflow, and only asks for its jobs
+    pub fn workflow(mut self, workflow: Option<&'a str>) -> Self {
+        self.workflow = workflow;
+        self
+    }
+
+    /// Asks for input with the given prompt instead of the terminal
+    pub fn prompt<Q: Prompt>(self, prompt: Q) -> Wizard<'a, Q> {
+        Wizard {
+            project: self.project,
+            workflow: self.workflow,
+            prompt,
+            dry_run: self.dry_run,
+            force: self.force,
+            offline: self.offline,
+        }
+    }
+
+    /// Only previews the workflow instead of asking to write it to disk
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Overwrites the workflow even if it has been modified by hand
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Reads templates only from the cache instead of downloading them
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    async fn select_workflow(&self, configuration: &Configuration) -> Result<String, Error> {
+        if let Some(workflow) = self.workflow {
+            return Ok(workflow.into());
+        }
+
+        let library = configuration.libraries().init(self.project, self.offline);
+        let mut workflows = library
+            .workflows()
+            .await
+            .context("failed to list workflows")?;
+
+        if workflows.is_empty() {
+            return Err(anyhow!("failed to find any workflows in {library}"));
+        }
+
+        let selection = self.prompt.select("Workflow", &workflows)?;
+
+        Ok(workflows.swap_remove(selection))
+    }
+
+    async fn select_jobs(
+        &self,
+        configuration: &Configuration,
+        workflow: &str,
+    ) -> Result<Vec<String>, Error> {
+        let library = configuration.libraries().init(self.project, self.offline);
+        let jobs = library
+            .jobs(workflow)
+            .await
+            .context(format!("failed to list jobs for workflow '{workflow}'"))?;
+
+        let configured_jobs: &[JobConfiguration] = configuration
+            .workflows()
+            .iter()
+            .find(|configured_workflow| configured_workflow.name() == workflow)
+            .map(|configured_workflow| configured_workflow.jobs())
+            .unwrap_or_default();
+        let defaults: Vec<bool> = jobs
+            .iter()
+            .map(|job| {
+                configured_jobs
+                    .iter()
+                    .any(|configured| configured.name() == job)
+            })
+            .collect();
+
+        let selection = self.prompt.multi_select(
+            "Jobs (space to select, enter to confirm)",
+            &jobs,
+            &defaults,
+        )?;
+        let selected: Vec<&str> = selection
+            .into_iter()
+            .map(|index| jobs[index].as_str())
+            .collect();
+
+        // Jobs that have been added more than once are kept by their id, so that each instance
+        // keeps its configuration. Jobs that were not offered, e.g. from another library, are kept
+        // as well, since they cannot have been deselected.
+        let mut keys: Vec<String> = configured_jobs
+            .iter()
+            .filter(|configured| {
+                !jobs.iter().any(|job| job == configured.name())
+                    || selected.contains(&configured.name())
+            })
+            .map(|configured| configured.key().to_string())
+            .collect();
+        keys.extend(
+            selected
+                .into_iter()
+                .filter(|job| {
+                    !configured_jobs
+                        .iter()
+                        .any(|configured| configured.name() == *job)
+                })
+                .map(String::from),
+        );
+
+        Ok(keys)
+    }
+}
+
+#[async_trait]
+impl<'a, P: Prompt> Command for Wizard<'a, P> {
+    async fn run(&self) -> Result<(), Error> {
+        if !self.prompt.is_interactive() {
+            return Err(anyhow!(
+                "the --workflow argument is required when not running in a terminal"
+            ));
+        }
+
+        let configuration = Configuration::load(self.project)?;
+
+        let workflow = self.select_workflow(&configuration).await?;
+        let jobs = self.select_jobs(&configuration, &workflow).await?;
+
+        let command = Create::new(self.project, &workflow, &jobs)
+            .force(self.force)
+            .offline(self.offline);
+        command.dry_run(true).run().await?;
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        let confirmed = self.prompt.confirm(&format!(
+            "Write {}?",
+            command.relative_workflow_path().display()
+        ))?;
+
+        if confirmed {
+            command.run().await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<P> Display for Wizard<'_, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "create")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use crate::cli::commands::local_project;
+    use crate::cli::configuration::WorkflowConfiguration;
+
+    use super::*;
+
+    /// Answers the prompts of the wizard with a script, and records the prompts it was asked
+    #[derive(Debug, Default)]
+    struct Script {
+        interactive: bool,
+        workflow: usize,
+        jobs: Vec<usize>,
+        confirm: bool,
+        prompts: Mutex<Vec<String>>,
+    }
+
+    impl Script {
+        fn new(workflow: usize, jobs: Vec<usize>, confirm: bool) -> Self {
+            Self {
+                interactive: true,
+                workflow,
+                jobs,
+                confirm,
+                prompts: Mutex::default(),
+            }
+        }
+
+        fn record(&self, prompt: &str) {
+            self.prompts.lock().unwrap().push(prompt.into());
+        }
+    }
+
+    impl Prompt for &Script {
+        fn is_interactive(&self) -> bool {
+            self.interactive
+        }
+
+        fn select(&self, prompt: &str, _items: &[String]) -> Result<usize, Error> {
+            self.record(prompt);
+            Ok(self.workflow)
+        }
+
+        fn multi_select(
+            &self,
+            prompt: &str,
+            _items: &[String],
+            _defaults: &[bool],
+        ) -> Result<Vec<usize>, Error> {
+            self.record(prompt);
+            Ok(self.jobs.clone())
+        }
+
+        fn confirm(&self, prompt: &str) -> Result<bool, Error> {
+            self.record(prompt);
+            Ok(self.confirm)
+        }
+    }
+
+    #[tokio::test]
+    async fn run_creates_selected_workflow() {
+        let test_project = local_project();
+        let script = Script::new(0, vec![0], true);
+
+        Wizard::new(test_project.project())
+            .prompt(&script)
+            .run()
+            .await
+            .unwrap();
+
+        let workflow =
+            std::fs::read_to_string(test_project.path().join(".github/workflows/rust.yml"))
+                .unwrap();
+        let configuration = Configuration::load(test_project.project()).unwrap();
+
+        assert!(workflow.contains("lint:\n"));
+        assert_eq!("lint", configuration.workflows()[0].jobs()[0].name());
+        assert_eq!(
+            vec![
+                "Workflow",
+                "Jobs (space to select, enter to confirm)",
+                "Write .github/workflows/rust.yml?"
+            ],
+            *script.prompts.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_with_workflow_only_asks_for_jobs() {
+        let test_project = local_project();
+        let script = Script::new(0, vec![0], true);
+
+        Wizard::new(test_project.project())
+            .workflow(Some("rust"))
+            .prompt(&script)
+            .run()
+            .await
+            .unwrap();
+
+        let workflow =
+            std::fs::read_to_string(test_project.path().join(".github/workflows/rust.yml"))
+                .unwrap();
+
+        assert!(workflow.contains("lint:\n"));
+        assert_eq!(
+            vec![
+                "Jobs (space to select, enter to confirm)",
+                "Write .github/workflows/rust.yml?"
+            ],
+            *script.prompts.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_without_confirmation_writes_nothing() {
+        let test_project = local_project();
+        let script = Script::new(0, vec![0], false);
+
+        Wizard::new(test_project.project())
+            .prompt(&script)
+            .run()
+            .await
+            .unwrap();
+
+        assert!(!test_project
+            .path()
+            .join(".github/workflows/rust.yml")
+            .exists());
+    }
+
+    #[tokio::test]
+    async fn run_with_dry_run_does_not_confirm() {
+        let test_project = local_project();
+        let script = Script::new(0, vec![0], true);
+
+        Wizard::new(test_project.project())
+            .dry_run(true)
+            .prompt(&script)
+            .run()
+            .await
+            .unwrap();
+
+        assert!(!test_project
+            .path()
+            .join(".github/workflows/rust.yml")
+            .exists());
+        assert_eq!(2, script.prompts.lock().unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn run_keeps_jobs_that_were_added_more_than_once() {
+        let test_project = local_project();
+        let script = Script::new(0, vec![0], true);
+
+        let mut configuration = Configuration::load(test_project.project()).unwrap();
+        configuration.add_workflow(
+            WorkflowConfiguration::builder()
+                .name("rust")
+                .jobs(vec![
+                    "lint".into(),
+                    JobConfiguration::builder()
+                        .name("lint")
+                        .id("lint-nightly")
+                        .build(),
+                ])
+                .build(),
+        );
+        configuration.save(test_project.project()).unwrap();
+
+        Wizard::new(test_project.project())
+            .workflow(Some("rust"))
+            .prompt(&script)
+            .run()
+            .await
+            .unwrap();
+
+        let configuration = Configuration::load(test_project.project()).unwrap();
+        let jobs = configuration.workflows()[0].jobs();
+
+        assert_eq!(2, jobs.len());
+        assert_eq!(Some("lint-nightly"), jobs[1].id());
+    }
+
+    #[tokio::test]
+    async fn run_errors_when_not_interactive() {
+        let test_project = local_project();
+        let script = Script::default();
+
+        let error = Wizard::new(test_project.project())
+            .prompt(&script)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "the --workflow argument is required when not running in a terminal",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        let project = Project::at(".".into()).unwrap();
+
+        assert_eq!("create", Wizard::new(&project).to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Terminal>();
+        assert_send::<Wizard>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Terminal>();
+        assert_sync::<Wizard>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Terminal>();
+        assert_unpin::<Wizard>();
+    }
+}
@@ -0,0 +1,256 @@
+use typed_builder::TypedBuilder;
+
+use crate::{Matrix, Variables};
+
+/// A job in a workflow
+///
+/// Jobs are written as plain names in the configuration file, unless they set an id, variables, or
+/// a matrix. In that case, they are written as a mapping with a `name` and these keys.
+///
+/// The `id` replaces the key of the job in the rendered workflow, which makes it possible to add
+/// the same job to a workflow more than once, e.g. with different variables.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, TypedBuilder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        from = "SerializedJobConfiguration",
+        into = "SerializedJobConfiguration"
+    )
+)]
+pub struct JobConfiguration {
+    #[builder(setter(into))]
+    name: String,
+    #[builder(default, setter(into, strip_option))]
+    id: Option<String>,
+    #[builder(default)]
+    variables: Variables,
+    #[builder(default)]
+    matrix: Matrix,
+}
+
+impl JobConfiguration {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the id that replaces the key of the job in the rendered workflow
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn variables(&self) -> &Variables {
+        &self.variables
+    }
+
+    /// Returns the matrix that is added to the `strategy.matrix` of the job
+    pub fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    /// Returns the id of the job, or its name if it has none, which identifies it in its workflow
+    pub(crate) fn key(&self) -> &str {
+        self.id().unwrap_or(&self.name)
+    }
+}
+
+impl From<&str> for JobConfiguration {
+    fn from(name: &str) -> Self {
+        Self::builder().name(name).build()
+    }
+}
+
+impl From<&String> for JobConfiguration {
+    fn from(name: &String) -> Self {
+        Self::builder().name(name).build()
+    }
+}
+
+impl From<String> for JobConfiguration {
+    fn from(name: String) -> Self {
+        Self::builder().name(name).build()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum SerializedJobConfiguration {
+    Name(String),
+    Job {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Variables::is_empty")]
+        variables: Variables,
+        #[serde(default, skip_serializing_if = "Matrix::is_empty")]
+        matrix: Matrix,
+    },
+}
+
+#[cfg(feature = "serde")]
+impl From<SerializedJobConfiguration> for JobConfiguration {
+    fn from(serialized: SerializedJobConfiguration) -> Self {
+        match serialized {
+            SerializedJobConfiguration::Name(name) => name.into(),
+            SerializedJobConfiguration::Job {
+                name,
+                id,
+                variables,
+                matrix,
+            } => Self {
+                name,
+                id,
+                variables,
+                matrix,
+            },
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<JobConfiguration> for SerializedJobConfiguration {
+    fn from(job: JobConfiguration) -> Self {
+        if job.id.is_none() && job.variables.is_empty() && job.matrix.is_empty() {
+            Self::Name(job.name)
+        } else {
+            Self::Job {
+                name: job.name,
+                id: job.id,
+                variables: job.variables,
+                matrix: job.matrix,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_name() {
+        let job: JobConfiguration = serde_yaml::from_str("lint").unwrap();
+
+        assert_eq!("lint", job.name());
+        assert!(job.variables().is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_variables() {
+        let yaml = indoc!(
+            r#"
+            ---
+            name: test
+            variables:
+              toolchain: nightly
+            "#
+        );
+
+        let job: JobConfiguration = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!("test", job.name());
+        assert_eq!(Some("nightly"), job.variables().get("toolchain"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_serialize_without_variables() {
+        let job = JobConfiguration::from("lint");
+
+        assert_eq!("lint\n", serde_yaml::to_string(&job).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_serialize_with_variables() {
+        let job = JobConfiguration::builder()
+            .name("test")
+            .variables(Variables::from_iter([("toolchain", "nightly")]))
+            .build();
+
+        let expected = indoc!(
+            r#"
+            name: test
+            variables:
+              toolchain: nightly
+            "#
+        );
+
+        assert_eq!(expected, serde_yaml::to_string(&job).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_id() {
+        let yaml = indoc!(
+            r#"
+            ---
+            name: test
+            id: test-core
+            "#
+        );
+
+        let job: JobConfiguration = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!("test", job.name());
+        assert_eq!(Some("test-core"), job.id());
+        assert_eq!("test-core", job.key());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_matrix() {
+        let yaml = indoc!(
+            r#"
+            ---
+            name: test
+            matrix:
+              os: [ubuntu-latest, macos-latest]
+            "#
+        );
+
+        let job: JobConfiguration = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(
+            Some(&["ubuntu-latest".into(), "macos-latest".into()][..]),
+            job.matrix().dimension("os")
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_serialize_with_id() {
+        let job = JobConfiguration::builder()
+            .name("test")
+            .id("test-core")
+            .build();
+
+        assert_eq!(
+            "name: test\nid: test-core\n",
+            serde_yaml::to_string(&job).unwrap()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<JobConfiguration>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<JobConfiguration>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<JobConfiguration>();
+    }
+}
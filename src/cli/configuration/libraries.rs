@@ -0,0 +1,411 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::cli::configuration::{LibraryConfiguration, WorkflowReference};
+use crate::fragment::split_reference;
+use crate::{FragmentLibrary, LayeredLibrary, Project};
+
+/// The name of the library in configurations that only have a single `library`
+const DEFAULT_LIBRARY: &str = "default";
+
+/// A library with a name, which can be used to reference its fragments, e.g. `org:rust/test`
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize, TypedBuilder,
+)]
+pub struct NamedLibraryConfiguration {
+    #[builder(setter(into))]
+    name: String,
+    #[serde(flatten)]
+    library: LibraryConfiguration,
+}
+
+impl NamedLibraryConfiguration {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn library(&self) -> &LibraryConfiguration {
+        &self.library
+    }
+}
+
+/// The libraries of a project in the order of their precedence
+///
+/// Libraries can be pinned to the commits in the lockfile, which replace their references when
+/// they are initialized without changing how they are displayed.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Libraries {
+    libraries: Vec<NamedLibraryConfiguration>,
+    commits: BTreeMap<String, String>,
+}
+
+impl Libraries {
+    /// Creates the libraries from a list, and checks that their names are unique and valid
+    pub fn new(libraries: Vec<NamedLibraryConfiguration>) -> Result<Self, String> {
+        if libraries.is_empty() {
+            return Err("at least one library must be configured".into());
+        }
+
+        for (index, library) in libraries.iter().enumerate() {
+            if library.name.is_empty() || library.name.contains([':', '/']) {
+                return Err(format!(
+                    "library name '{}' must not be empty or contain ':' or '/'",
+                    library.name
+                ));
+            }
+            if libraries[..index]
+                .iter()
+                .any(|other| other.name == library.name)
+            {
+                return Err(format!("library '{}' is configured twice", library.name));
+            }
+        }
+
+        Ok(Self {
+            libraries,
+            commits: BTreeMap::new(),
+        })
+    }
+
+    pub fn libraries(&self) -> &[NamedLibraryConfiguration] {
+        &self.libraries
+    }
+
+    /// Returns the library if the configuration has only a single, unnamed `library`
+    pub(crate) fn default_library(&self) -> Option<&LibraryConfiguration> {
+        match self.libraries.as_slice() {
+            [library] if library.name == DEFAULT_LIBRARY => Some(&library.library),
+            _ => None,
+        }
+    }
+
+    /// Overrides the branch, tag, or commit of the libraries that a workflow is read from
+    ///
+    /// A single reference applies to the only library of the project, or to the library that the
+    /// workflow is qualified with. Otherwise, it is not clear which library the reference belongs
+    /// to, and the reference must name it.
+    pub(crate) fn set_reference(
+        &mut self,
+        reference: &WorkflowReference,
+        workflow: &str,
+    ) -> Result<(), String> {
+        let references = match reference {
+            WorkflowReference::Single(reference) => {
+                if let Some(library) = self.default_library_mut() {
+                    library.set_reference(reference);
+                    return Ok(());
+                }
+
+                let Some(library) = split_reference(workflow).0 else {
+                    return Err(format!(
+                        "workflow '{workflow}' must name the library of its `ref`, e.g. `ref:                          {{<library>: {reference}}}`"
+                    ));
+                };
+
+                BTreeMap::from([(library.to_string(), reference.clone())])
+            }
+            WorkflowReference::Libraries(references) => references.clone(),
+        };
+
+        for (name, reference) in references {
+            let library = self
+                .libraries
+                .iter_mut()
+                .find(|library| library.name == name)
+                .ok_or_else(|| format!("library '{name}' is not configured"))?;
+
+            if !library.library.set_reference(&reference) {
+                return Err(format!("library '{name}' does not support references"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pins the libraries to commits by their name, e.g. to the commits in the lockfile
+    pub(crate) fn pin(&mut self, commits: &BTreeMap<String, String>) {
+        self.commits = commits.clone();
+    }
+
+    fn default_library_mut(&mut self) -> Option<&mut LibraryConfiguration> {
+        match self.libraries.as_mut_slice() {
+            [library] if library.name == DEFAULT_LIBRARY => Some(&mut library.library),
+            _ => None,
+        }
+    }
+
+    /// Initializes the libraries, searching them in the order of their precedence
+    pub(crate) fn init(
+        &self,
+        project: &Project,
+        offline: bool,
+    ) -> Box<dyn for<'a> FragmentLibrary<'a>> {
+        if self.default_library().is_some() {
+            return self.pinned(&self.libraries[0]).init(project, offline);
+        }
+
+        Box::new(LayeredLibrary::new(
+            self.libraries
+                .iter()
+                .map(|library| {
+                    let pinned = self.pinned(library);
+                    (library.name.clone(), pinned.init(project, offline))
+                })
+                .collect(),
+        ))
+    }
+
+    /// Returns the configuration of a library, with its reference replaced by its pinned commit
+    fn pinned(&self, library: &NamedLibraryConfiguration) -> LibraryConfiguration {
+        let mut configuration = library.library.clone();
+
+        if let Some(commit) = self.commits.get(&library.name) {
+            configuration.set_reference(commit);
+        }
+
+        configuration
+    }
+}
+
+impl From<LibraryConfiguration> for Libraries {
+    fn from(library: LibraryConfiguration) -> Self {
+        Self {
+            libraries: vec![NamedLibraryConfiguration {
+                name: DEFAULT_LIBRARY.into(),
+                library,
+            }],
+            commits: BTreeMap::new(),
+        }
+    }
+}
+
+impl Display for Libraries {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(library) = self.default_library() {
+            return write!(f, "{library}");
+        }
+
+        let libraries: Vec<String> = self
+            .libraries
+            .iter()
+            .map(|library| format!("{}: {}", library.name, library.library))
+            .collect();
+
+        write!(f, "{}", libraries.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::builtin::BuiltinConfiguration;
+    use crate::github::GitHubConfiguration;
+    use crate::local::LocalConfiguration;
+
+    use super::*;
+
+    fn libraries() -> Libraries {
+        Libraries::new(vec![
+            NamedLibraryConfiguration::builder()
+                .name("local")
+                .library(LibraryConfiguration::Local(
+                    LocalConfiguration::builder().path("templates").build(),
+                ))
+                .build(),
+            NamedLibraryConfiguration::builder()
+                .name("org")
+                .library(LibraryConfiguration::GitHub(
+                    GitHubConfiguration::builder()
+                        .owner("jdno")
+                        .repository("workflows")
+                        .build(),
+                ))
+                .build(),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn new_rejects_duplicate_names() {
+        let library = libraries().libraries()[0].clone();
+
+        let error = Libraries::new(vec![library.clone(), library]).unwrap_err();
+
+        assert_eq!("library 'local' is configured twice", error);
+    }
+
+    #[test]
+    fn new_rejects_invalid_names() {
+        let library = NamedLibraryConfiguration::builder()
+            .name("org:local")
+            .library(LibraryConfiguration::Local(
+                LocalConfiguration::builder().path("templates").build(),
+            ))
+            .build();
+
+        assert!(Libraries::new(vec![library]).is_err());
+    }
+
+    #[test]
+    fn set_reference_of_default_library() {
+        let mut libraries = Libraries::from(LibraryConfiguration::GitHub(
+            GitHubConfiguration::builder()
+                .owner("jdno")
+                .repository("workflows")
+                .build(),
+        ));
+
+        libraries
+            .set_reference(&WorkflowReference::from("v2.0.0"), "rust")
+            .unwrap();
+
+        assert_eq!("github jdno/workflows@v2.0.0", libraries.to_string());
+    }
+
+    #[test]
+    fn set_reference_of_qualified_workflow() {
+        let mut libraries = libraries();
+
+        libraries
+            .set_reference(&WorkflowReference::from("v2.0.0"), "org:rust")
+            .unwrap();
+
+        assert_eq!(
+            "local: local templates, org: github jdno/workflows@v2.0.0",
+            libraries.to_string()
+        );
+    }
+
+    #[test]
+    fn set_reference_per_library() {
+        let mut libraries = libraries();
+        let reference =
+            WorkflowReference::Libraries(BTreeMap::from([("org".into(), "v2.0.0".into())]));
+
+        libraries.set_reference(&reference, "rust").unwrap();
+
+        assert_eq!(
+            "local: local templates, org: github jdno/workflows@v2.0.0",
+            libraries.to_string()
+        );
+    }
+
+    #[test]
+    fn set_reference_requires_library_of_unqualified_workflow() {
+        let mut libraries = libraries();
+
+        let error = libraries
+            .set_reference(&WorkflowReference::from("v2.0.0"), "rust")
+            .unwrap_err();
+
+        assert!(error.contains("must name the library"));
+    }
+
+    #[test]
+    fn set_reference_rejects_unknown_library() {
+        let mut libraries = libraries();
+        let reference =
+            WorkflowReference::Libraries(BTreeMap::from([("team".into(), "v2.0.0".into())]));
+
+        let error = libraries.set_reference(&reference, "rust").unwrap_err();
+
+        assert_eq!("library 'team' is not configured", error);
+    }
+
+    #[test]
+    fn set_reference_rejects_local_libraries() {
+        let mut libraries = libraries();
+        let reference =
+            WorkflowReference::Libraries(BTreeMap::from([("local".into(), "v2.0.0".into())]));
+
+        let error = libraries.set_reference(&reference, "rust").unwrap_err();
+
+        assert_eq!("library 'local' does not support references", error);
+    }
+
+    #[test]
+    fn trait_deserialize_named_library() {
+        let yaml = indoc!(
+            r#"
+            ---
+            name: org
+            github:
+              owner: jdno
+              repository: workflows
+            "#
+        );
+
+        let library: NamedLibraryConfiguration = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!("org", library.name());
+        assert!(matches!(library.library(), LibraryConfiguration::GitHub(_)));
+    }
+
+    #[test]
+    fn trait_serialize_builtin_library() {
+        let library = NamedLibraryConfiguration::builder()
+            .name("builtin")
+            .library(LibraryConfiguration::Builtin(BuiltinConfiguration::new()))
+            .build();
+
+        let yaml = serde_yaml::to_string(&library).unwrap();
+
+        assert_eq!("name: builtin\nbuiltin: {}\n", yaml);
+        assert_eq!(library, serde_yaml::from_str(&yaml).unwrap());
+    }
+
+    #[test]
+    fn trait_display_default_library() {
+        let libraries = Libraries::from(LibraryConfiguration::Local(
+            LocalConfiguration::builder().path("templates").build(),
+        ));
+
+        assert_eq!("local templates", libraries.to_string());
+    }
+
+    #[test]
+    fn trait_display_named_libraries() {
+        assert_eq!(
+            "local: local templates, org: github jdno/workflows",
+            libraries().to_string()
+        );
+    }
+
+    #[test]
+    fn trait_serialize_named_library() {
+        let yaml = indoc!(
+            r#"
+            name: local
+            local:
+              path: templates
+            "#
+        );
+
+        assert_eq!(
+            yaml,
+            serde_yaml::to_string(&libraries().libraries()[0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Libraries>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Libraries>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Libraries>();
+    }
+}
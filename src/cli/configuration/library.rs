@@ -1,19 +1,161 @@
+use std::fmt::{Display, Formatter};
+
 use serde::{Deserialize, Serialize};
 
-use crate::github::GitHubConfiguration;
-use crate::local::LocalConfiguration;
+use crate::archive::{ArchiveConfiguration, ArchiveLibrary};
+use crate::builtin::{BuiltinConfiguration, BuiltinLibrary};
+use crate::git::{GitConfiguration, GitLibrary};
+use crate::github::{GitHubConfiguration, GitHubLibrary};
+use crate::local::{LocalConfiguration, LocalLibrary};
+use crate::{Cache, FragmentLibrary, Project};
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LibraryConfiguration {
+    Archive(ArchiveConfiguration),
+    Builtin(BuiltinConfiguration),
+    Git(GitConfiguration),
     GitHub(GitHubConfiguration),
     Local(LocalConfiguration),
 }
 
+impl LibraryConfiguration {
+    /// Overrides the branch, tag, or commit of libraries that support references
+    ///
+    /// Returns `false` if the library does not support references.
+    pub(crate) fn set_reference(&mut self, reference: &str) -> bool {
+        match self {
+            LibraryConfiguration::Git(git) => git.set_reference(reference),
+            LibraryConfiguration::GitHub(github) => github.set_reference(reference),
+            LibraryConfiguration::Archive(_)
+            | LibraryConfiguration::Builtin(_)
+            | LibraryConfiguration::Local(_) => return false,
+        }
+
+        true
+    }
+
+    /// Initializes the library that the configuration points to
+    ///
+    /// Remote libraries cache the fragments that they download. In offline mode, fragments are only
+    /// read from the cache.
+    pub(crate) fn init(
+        &self,
+        project: &Project,
+        offline: bool,
+    ) -> Box<dyn for<'a> FragmentLibrary<'a>> {
+        match self {
+            LibraryConfiguration::Archive(archive_configuration) => Box::new(
+                ArchiveLibrary::new(project, archive_configuration.clone(), working_cache())
+                    .offline(offline),
+            ),
+            LibraryConfiguration::Builtin(_) => Box::new(BuiltinLibrary::new()),
+            LibraryConfiguration::Git(git_configuration) => Box::new(
+                GitLibrary::new(git_configuration.clone(), working_cache()).offline(offline),
+            ),
+            LibraryConfiguration::GitHub(github_configuration) => {
+                let mut library = GitHubLibrary::new(github_configuration.clone()).offline(offline);
+                if let Some(cache) = Cache::discover() {
+                    library = library.cache(cache);
+                }
+
+                Box::new(library)
+            }
+            LibraryConfiguration::Local(local_configuration) => {
+                Box::new(LocalLibrary::new(project, local_configuration))
+            }
+        }
+    }
+}
+
+impl Display for LibraryConfiguration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LibraryConfiguration::Archive(archive) => write!(f, "archive {}", archive.url()),
+            LibraryConfiguration::Builtin(_) => write!(f, "builtin"),
+            LibraryConfiguration::Git(git) => {
+                write!(f, "git {}", git.url())?;
+
+                if let Some(reference) = git.reference() {
+                    write!(f, "@{reference}")?;
+                }
+
+                Ok(())
+            }
+            LibraryConfiguration::GitHub(github) => {
+                write!(f, "github {}/{}", github.owner(), github.repository())?;
+
+                if let Some(reference) = github.reference() {
+                    write!(f, "@{reference}")?;
+                }
+
+                Ok(())
+            }
+            LibraryConfiguration::Local(local) => write!(f, "local {}", local.path().display()),
+        }
+    }
+}
+
+/// Returns the cache for libraries that must extract or clone their fragments somewhere, even if
+/// the user has no cache directory
+fn working_cache() -> Cache {
+    Cache::discover().unwrap_or_else(|| Cache::new(std::env::temp_dir().join("flowcrafter")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn trait_display_archive() {
+        let library = LibraryConfiguration::Archive(
+            ArchiveConfiguration::builder()
+                .url("https://artifacts.example.com/templates.tar.gz")
+                .build(),
+        );
+
+        assert_eq!(
+            "archive https://artifacts.example.com/templates.tar.gz",
+            library.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_display_git() {
+        let library = LibraryConfiguration::Git(
+            GitConfiguration::builder()
+                .url("https://gitlab.com/jdno/workflows.git")
+                .reference("main")
+                .build(),
+        );
+
+        assert_eq!(
+            "git https://gitlab.com/jdno/workflows.git@main",
+            library.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_display_github() {
+        let library = LibraryConfiguration::GitHub(
+            GitHubConfiguration::builder()
+                .owner("jdno")
+                .repository("workflows")
+                .reference("v1.0.0")
+                .build(),
+        );
+
+        assert_eq!("github jdno/workflows@v1.0.0", library.to_string());
+    }
+
+    #[test]
+    fn trait_display_local() {
+        let library =
+            LibraryConfiguration::Local(LocalConfiguration::builder().path("templates").build());
+
+        assert_eq!("local templates", library.to_string());
+    }
+
     #[test]
     fn trait_send() {
         fn assert_send<T: Send>() {}
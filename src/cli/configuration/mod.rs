@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 
 use anyhow::{Context, Error};
 use indoc::indoc;
@@ -7,10 +8,16 @@ use typed_builder::TypedBuilder;
 
 use crate::Project;
 
+pub use self::job::JobConfiguration;
+pub use self::libraries::{Libraries, NamedLibraryConfiguration};
 pub use self::library::LibraryConfiguration;
+pub use self::reference::WorkflowReference;
 pub use self::workflow::WorkflowConfiguration;
 
+mod job;
+mod libraries;
 mod library;
+mod reference;
 mod workflow;
 
 const CONFIG_FILE_NAME: &str = "flowcrafter.yml";
@@ -22,13 +29,19 @@ const CONFIG_FILE_HEADER: &str = indoc!(
     "#
 );
 
+/// The configuration of FlowCrafter in a project
+///
+/// Projects with a single template library configure it as `library`. Projects that combine
+/// libraries configure them as a list of named `libraries` instead, in the order of their
+/// precedence.
 #[derive(
     Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize, TypedBuilder,
 )]
+#[serde(try_from = "SerializedConfiguration", into = "SerializedConfiguration")]
 pub struct Configuration {
-    #[serde(with = "serde_yaml::with::singleton_map")]
-    library: LibraryConfiguration,
-    #[serde(default)]
+    #[builder(setter(into))]
+    libraries: Libraries,
+    #[builder(default)]
     workflows: Vec<WorkflowConfiguration>,
 }
 
@@ -37,15 +50,32 @@ impl Configuration {
         &self.workflows
     }
 
-    pub fn add_workflow(&mut self, workflow: WorkflowConfiguration) {
-        for existing_workflow in &mut self.workflows {
-            if existing_workflow.name() == workflow.name() {
-                existing_workflow.set_jobs(workflow.jobs().to_vec());
-                return;
+    pub fn add_workflow(&mut self, workflow: WorkflowConfiguration) -> &WorkflowConfiguration {
+        let position = self
+            .workflows
+            .iter()
+            .position(|existing_workflow| existing_workflow.name() == workflow.name());
+
+        match position {
+            Some(position) => {
+                self.workflows[position].set_jobs(workflow.jobs().to_vec());
+                &self.workflows[position]
+            }
+            None => {
+                self.workflows.push(workflow);
+                &self.workflows[self.workflows.len() - 1]
             }
         }
+    }
+
+    /// Removes a workflow from the configuration and returns it
+    pub fn remove_workflow(&mut self, name: &str) -> Option<WorkflowConfiguration> {
+        let position = self
+            .workflows
+            .iter()
+            .position(|workflow| workflow.name() == name)?;
 
-        self.workflows.push(workflow);
+        Some(self.workflows.remove(position))
     }
 
     pub fn save(&self, project: &Project) -> Result<(), Error> {
@@ -55,20 +85,14 @@ impl Configuration {
                 .context("failed to create .github directory in project")?;
         }
 
-        let config_path = github_path.join(CONFIG_FILE_NAME);
-
-        let serialized =
-            serde_yaml::to_string(self).context("failed to serialize configuration to YAML")?;
-        let config_with_header = format!("{}{}", CONFIG_FILE_HEADER, serialized);
-
-        std::fs::write(config_path, config_with_header)
+        std::fs::write(Self::path(project), self.to_yaml()?)
             .context("failed to write configuration to file")?;
 
         Ok(())
     }
 
     pub fn load(project: &Project) -> Result<Self, Error> {
-        let config_path = project.path().join(".github").join(CONFIG_FILE_NAME);
+        let config_path = Self::path(project);
 
         let serialized =
             std::fs::read_to_string(config_path).context("failed to read configuration file")?;
@@ -79,8 +103,64 @@ impl Configuration {
         Ok(config)
     }
 
-    pub fn library(&self) -> &LibraryConfiguration {
-        &self.library
+    pub fn libraries(&self) -> &Libraries {
+        &self.libraries
+    }
+
+    /// Returns the path of the configuration file in the project
+    pub(crate) fn path(project: &Project) -> PathBuf {
+        project.path().join(".github").join(CONFIG_FILE_NAME)
+    }
+
+    /// Serializes the configuration to the contents of the configuration file
+    pub(crate) fn to_yaml(&self) -> Result<String, Error> {
+        let serialized =
+            serde_yaml::to_string(self).context("failed to serialize configuration to YAML")?;
+
+        Ok(format!("{}{}", CONFIG_FILE_HEADER, serialized))
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct SerializedConfiguration {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "serde_yaml::with::singleton_map")]
+    library: Option<LibraryConfiguration>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    libraries: Vec<NamedLibraryConfiguration>,
+    #[serde(default)]
+    workflows: Vec<WorkflowConfiguration>,
+}
+
+impl TryFrom<SerializedConfiguration> for Configuration {
+    type Error = String;
+
+    fn try_from(serialized: SerializedConfiguration) -> Result<Self, Self::Error> {
+        let libraries = match (serialized.library, serialized.libraries.is_empty()) {
+            (Some(library), true) => library.into(),
+            (None, false) => Libraries::new(serialized.libraries)?,
+            _ => return Err("configure either a `library` or a list of `libraries`".into()),
+        };
+
+        Ok(Self {
+            libraries,
+            workflows: serialized.workflows,
+        })
+    }
+}
+
+impl From<Configuration> for SerializedConfiguration {
+    fn from(configuration: Configuration) -> Self {
+        let (library, libraries) = match configuration.libraries.default_library() {
+            Some(library) => (Some(library.clone()), Vec::new()),
+            None => (None, configuration.libraries.libraries().to_vec()),
+        };
+
+        Self {
+            library,
+            libraries,
+            workflows: configuration.workflows,
+        }
     }
 }
 
@@ -95,6 +175,7 @@ mod tests {
     use indoc::indoc;
     use tempfile::{tempdir, TempDir};
 
+    use crate::builtin::BuiltinConfiguration;
     use crate::github::GitHubConfiguration;
 
     use super::*;
@@ -117,7 +198,7 @@ mod tests {
 
     fn configuration() -> Configuration {
         Configuration::builder()
-            .library(LibraryConfiguration::GitHub(
+            .libraries(LibraryConfiguration::GitHub(
                 GitHubConfiguration::builder()
                     .owner("jdno")
                     .repository("flowcrafter")
@@ -145,6 +226,24 @@ mod tests {
         temp_dir
     }
 
+    #[test]
+    fn remove_workflow_returns_workflow() {
+        let mut configuration = configuration();
+
+        let workflow = configuration.remove_workflow("rust").unwrap();
+
+        assert_eq!("rust", workflow.name());
+        assert!(configuration.workflows().is_empty());
+    }
+
+    #[test]
+    fn remove_workflow_without_workflow() {
+        let mut configuration = configuration();
+
+        assert!(configuration.remove_workflow("python").is_none());
+        assert_eq!(1, configuration.workflows().len());
+    }
+
     #[test]
     fn save_writes_configuration() {
         let project_directory = temp_dir();
@@ -205,8 +304,8 @@ mod tests {
         let loaded_config = Configuration::load(&project).unwrap();
 
         assert!(matches!(
-            loaded_config.library,
-            LibraryConfiguration::GitHub(_)
+            loaded_config.libraries.default_library(),
+            Some(LibraryConfiguration::GitHub(_))
         ));
     }
 
@@ -229,11 +328,97 @@ mod tests {
         let configuration: Configuration = serde_yaml::from_str(SERIALIZED_CONFIGURATION).unwrap();
 
         assert!(matches!(
-            configuration.library,
-            LibraryConfiguration::GitHub(_)
+            configuration.libraries.default_library(),
+            Some(LibraryConfiguration::GitHub(_))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_git_library() {
+        let yaml = indoc!(
+            r#"
+            ---
+            library:
+              git:
+                url: https://gitlab.com/jdno/workflows.git
+                ref: v1.0.0
+            "#
+        );
+
+        let configuration: Configuration = serde_yaml::from_str(yaml).unwrap();
+
+        assert!(matches!(
+            configuration.libraries.default_library(),
+            Some(LibraryConfiguration::Git(_))
         ));
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_serialize_with_builtin_library() {
+        let configuration = Configuration::builder()
+            .libraries(LibraryConfiguration::Builtin(BuiltinConfiguration::new()))
+            .build();
+
+        let yaml = serde_yaml::to_string(&configuration).unwrap();
+
+        assert_eq!("library:\n  builtin: {}\nworkflows: []\n", yaml);
+        assert_eq!(configuration, serde_yaml::from_str(&yaml).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_libraries() {
+        let yaml = indoc!(
+            r#"
+            ---
+            libraries:
+              - name: local
+                local:
+                  path: .github/templates
+              - name: org
+                github:
+                  owner: jdno
+                  repository: workflows
+            "#
+        );
+
+        let configuration: Configuration = serde_yaml::from_str(yaml).unwrap();
+        let names: Vec<&str> = configuration
+            .libraries()
+            .libraries()
+            .iter()
+            .map(|library| library.name())
+            .collect();
+
+        assert_eq!(vec!["local", "org"], names);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_library_and_libraries() {
+        let yaml = indoc!(
+            r#"
+            ---
+            library:
+              local:
+                path: templates
+            libraries:
+              - name: org
+                local:
+                  path: templates
+            "#
+        );
+
+        let error = serde_yaml::from_str::<Configuration>(yaml).unwrap_err();
+
+        assert_eq!(
+            "configure either a `library` or a list of `libraries`",
+            error.to_string()
+        );
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn trait_deserialize_without_workflows() {
@@ -250,8 +435,8 @@ mod tests {
         let configuration: Configuration = serde_yaml::from_str(yaml).unwrap();
 
         assert!(matches!(
-            configuration.library,
-            LibraryConfiguration::GitHub(_)
+            configuration.libraries.default_library(),
+            Some(LibraryConfiguration::GitHub(_))
         ));
     }
 
@@ -288,6 +473,28 @@ mod tests {
         assert_eq!(expected, yaml);
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_serialize_with_libraries() {
+        let yaml = indoc!(
+            r#"
+            libraries:
+            - name: local
+              local:
+                path: .github/templates
+            - name: org
+              github:
+                owner: jdno
+                repository: workflows
+            workflows: []
+            "#
+        );
+
+        let configuration: Configuration = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(yaml, serde_yaml::to_string(&configuration).unwrap());
+    }
+
     #[test]
     fn trait_sync() {
         fn assert_sync<T: Sync>() {}
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+/// The branch, tag, or commit that a workflow reads its templates from
+///
+/// A single reference applies to the library of a project with only one `library`, or to the
+/// library that the workflow is read from if its name is qualified, e.g. `org:rust`. Projects with
+/// more than one library can override the reference of each library by its name instead.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum WorkflowReference {
+    /// A reference for the library of the workflow, e.g. `ref: v2.0.0`
+    Single(String),
+    /// A reference for each library by its name, e.g. `ref: {org: v2.0.0}`
+    Libraries(BTreeMap<String, String>),
+}
+
+impl From<&str> for WorkflowReference {
+    fn from(reference: &str) -> Self {
+        Self::Single(reference.into())
+    }
+}
+
+impl From<String> for WorkflowReference {
+    fn from(reference: String) -> Self {
+        Self::Single(reference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_single() {
+        let reference: WorkflowReference = serde_yaml::from_str("v2.0.0").unwrap();
+
+        assert_eq!(WorkflowReference::from("v2.0.0"), reference);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_libraries() {
+        let yaml = indoc!(
+            r#"
+            ---
+            org: v2.0.0
+            "#
+        );
+
+        let reference: WorkflowReference = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(
+            WorkflowReference::Libraries(BTreeMap::from([("org".into(), "v2.0.0".into())])),
+            reference
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<WorkflowReference>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<WorkflowReference>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<WorkflowReference>();
+    }
+}
@@ -1,12 +1,30 @@
 use typed_builder::TypedBuilder;
 
+use crate::cli::configuration::{JobConfiguration, WorkflowReference};
+use crate::{DuplicateJobs, Variables};
+
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, TypedBuilder)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WorkflowConfiguration {
     #[builder(setter(into))]
     name: String,
-    #[builder(setter(into))]
-    jobs: Vec<String>,
+    jobs: Vec<JobConfiguration>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Variables::is_empty"))]
+    #[builder(default)]
+    variables: Variables,
+    #[cfg_attr(feature = "serde", serde(rename = "ref"))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[builder(default, setter(into, strip_option))]
+    reference: Option<WorkflowReference>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip_serializing_if = "DuplicateJobs::is_error")
+    )]
+    #[builder(default)]
+    duplicates: DuplicateJobs,
 }
 
 impl WorkflowConfiguration {
@@ -14,12 +32,39 @@ impl WorkflowConfiguration {
         &self.name
     }
 
-    pub fn jobs(&self) -> &[String] {
+    pub fn jobs(&self) -> &[JobConfiguration] {
         &self.jobs
     }
 
-    pub fn set_jobs(&mut self, jobs: Vec<String>) {
-        self.jobs = jobs;
+    pub fn variables(&self) -> &Variables {
+        &self.variables
+    }
+
+    /// Returns the reference that overrides the ones of the libraries for this workflow
+    pub fn reference(&self) -> Option<&WorkflowReference> {
+        self.reference.as_ref()
+    }
+
+    /// Returns the strategy for jobs that are defined by more than one fragment
+    pub fn duplicates(&self) -> DuplicateJobs {
+        self.duplicates
+    }
+
+    /// Replaces the jobs of the workflow, keeping the configuration of jobs that already exist
+    ///
+    /// Jobs are matched by their id, or by their name if they have none, so that a job that has
+    /// been added more than once keeps the configuration of each of its instances.
+    pub fn set_jobs(&mut self, jobs: Vec<JobConfiguration>) {
+        self.jobs = jobs
+            .into_iter()
+            .map(|job| {
+                self.jobs
+                    .iter()
+                    .find(|existing_job| existing_job.key() == job.key())
+                    .cloned()
+                    .unwrap_or(job)
+            })
+            .collect();
     }
 }
 
@@ -64,7 +109,117 @@ mod tests {
             serde_yaml::from_str(yaml).expect("failed to deserialize YAML");
 
         assert_eq!(config.name(), "test");
-        assert_eq!(config.jobs(), &["lint", "style"]);
+        assert_eq!(
+            config.jobs(),
+            &[
+                JobConfiguration::from("lint"),
+                JobConfiguration::from("style")
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_variables() {
+        let yaml = indoc!(
+            r#"
+            ---
+            name: test
+            jobs:
+              - lint
+              - name: test
+                variables:
+                  toolchain: nightly
+            variables:
+              toolchain: stable
+            "#
+        );
+
+        let config: WorkflowConfiguration =
+            serde_yaml::from_str(yaml).expect("failed to deserialize YAML");
+
+        assert_eq!(Some("stable"), config.variables().get("toolchain"));
+        assert_eq!(
+            Some("nightly"),
+            config.jobs()[1].variables().get("toolchain")
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_reference() {
+        let yaml = indoc!(
+            r#"
+            ---
+            name: test
+            jobs: []
+            ref: v2.0.0
+            "#
+        );
+
+        let config: WorkflowConfiguration =
+            serde_yaml::from_str(yaml).expect("failed to deserialize YAML");
+
+        assert_eq!(Some(&WorkflowReference::from("v2.0.0")), config.reference());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_reference_per_library() {
+        let yaml = indoc!(
+            r#"
+            ---
+            name: test
+            jobs: []
+            ref:
+              org: v2.0.0
+            "#
+        );
+
+        let config: WorkflowConfiguration =
+            serde_yaml::from_str(yaml).expect("failed to deserialize YAML");
+
+        assert!(matches!(
+            config.reference(),
+            Some(WorkflowReference::Libraries(references)) if references["org"] == "v2.0.0"
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_duplicates() {
+        let yaml = indoc!(
+            r#"
+            ---
+            name: test
+            jobs: []
+            duplicates: rename
+            "#
+        );
+
+        let config: WorkflowConfiguration =
+            serde_yaml::from_str(yaml).expect("failed to deserialize YAML");
+
+        assert_eq!(DuplicateJobs::Rename, config.duplicates());
+    }
+
+    #[test]
+    fn set_jobs_keeps_existing_configuration() {
+        let mut config = WorkflowConfiguration::builder()
+            .name("test")
+            .jobs(vec![JobConfiguration::builder()
+                .name("test")
+                .variables(Variables::from_iter([("toolchain", "nightly")]))
+                .build()])
+            .build();
+
+        config.set_jobs(vec!["lint".into(), "test".into()]);
+
+        assert_eq!("lint", config.jobs()[0].name());
+        assert_eq!(
+            Some("nightly"),
+            config.jobs()[1].variables().get("toolchain")
+        );
     }
 
     #[test]
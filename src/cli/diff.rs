@@ -0,0 +1,60 @@
+use similar::TextDiff;
+
+/// Returns a unified diff between the current and the new contents of a file
+///
+/// Returns `None` if the contents are identical. Missing files are compared as empty files.
+pub(crate) fn unified_diff(path: &str, current: &str, new: &str) -> Option<String> {
+    if current == new {
+        return None;
+    }
+
+    let diff = TextDiff::from_lines(current, new)
+        .unified_diff()
+        .header(&format!("a/{path}"), &format!("b/{path}"))
+        .to_string();
+
+    Some(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn unified_diff_without_changes() {
+        assert!(unified_diff("workflow.yml", "name: Rust\n", "name: Rust\n").is_none());
+    }
+
+    #[test]
+    fn unified_diff_with_changes() {
+        let diff = unified_diff(
+            ".github/workflows/rust.yml",
+            "name: Rust\non: push\n",
+            "name: Rust\non: pull_request\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                --- a/.github/workflows/rust.yml
+                +++ b/.github/workflows/rust.yml
+                @@ -1,2 +1,2 @@
+                 name: Rust
+                -on: push
+                +on: pull_request
+                "#
+            ),
+            diff
+        );
+    }
+
+    #[test]
+    fn unified_diff_with_new_file() {
+        let diff = unified_diff("rust.yml", "", "name: Rust\n").unwrap();
+
+        assert!(diff.contains("+name: Rust\n"));
+    }
+}
@@ -0,0 +1,400 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use anyhow::{anyhow, Context, Error};
+use indoc::indoc;
+use serde::{Deserialize, Serialize};
+
+use crate::{Fragment, Job, Project};
+
+const LOCKFILE_NAME: &str = "flowcrafter.lock";
+const LOCKFILE_HEADER: &str = indoc!(
+    r#"
+    # This file is generated by FlowCrafter. It records the revisions of the
+    # templates that were used to render the workflows, and should not be edited.
+    ---
+    "#
+);
+
+/// The revisions of the fragments that were used to render the workflows in a project
+///
+/// Libraries in a repository are locked to the commit that the fragments were read from, and
+/// workflows are rendered from that commit until the lockfile is refreshed. Fragments in all other
+/// libraries are not pinned, but their revisions detect when they changed.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Deserialize, Serialize)]
+pub struct Lockfile {
+    #[serde(default)]
+    workflows: Vec<LockedWorkflow>,
+}
+
+impl Lockfile {
+    pub fn workflows(&self) -> &[LockedWorkflow] {
+        &self.workflows
+    }
+
+    pub fn workflow(&self, name: &str) -> Option<&LockedWorkflow> {
+        self.workflows
+            .iter()
+            .find(|workflow| workflow.name() == name)
+    }
+
+    pub fn add_workflow(&mut self, workflow: LockedWorkflow) {
+        match self
+            .workflows
+            .iter_mut()
+            .find(|existing_workflow| existing_workflow.name() == workflow.name())
+        {
+            Some(existing_workflow) => *existing_workflow = workflow,
+            None => self.workflows.push(workflow),
+        }
+    }
+
+    pub fn remove_workflow(&mut self, name: &str) {
+        self.workflows.retain(|workflow| workflow.name() != name);
+    }
+
+    pub fn save(&self, project: &Project) -> Result<(), Error> {
+        let github_path = project.path().join(".github");
+        if !github_path.exists() {
+            std::fs::create_dir_all(github_path.clone())
+                .context("failed to create .github directory in project")?;
+        }
+
+        let serialized =
+            serde_yaml::to_string(self).context("failed to serialize lockfile to YAML")?;
+        let lockfile_with_header = format!("{}{}", LOCKFILE_HEADER, serialized);
+
+        std::fs::write(github_path.join(LOCKFILE_NAME), lockfile_with_header)
+            .context("failed to write lockfile")
+    }
+
+    /// Loads the lockfile of the project, or returns an empty lockfile if none exists yet
+    pub fn load(project: &Project) -> Result<Self, Error> {
+        let lockfile_path = project.path().join(".github").join(LOCKFILE_NAME);
+
+        if !lockfile_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let serialized =
+            std::fs::read_to_string(lockfile_path).context("failed to read lockfile")?;
+
+        serde_yaml::from_str(&serialized).context("failed to deserialize lockfile")
+    }
+}
+
+impl Display for Lockfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Lockfile")
+    }
+}
+
+/// The library and revisions of the fragments that were used to render a workflow
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Deserialize, Serialize)]
+pub struct LockedWorkflow {
+    name: String,
+    source: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    commits: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    revision: Option<String>,
+    #[serde(default)]
+    jobs: Vec<LockedFragment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    steps: Vec<LockedFragment>,
+}
+
+impl LockedWorkflow {
+    pub fn new(source: impl Into<String>, workflow: &Fragment, jobs: &[Job]) -> Self {
+        Self {
+            name: workflow.name().into(),
+            source: source.into(),
+            commits: BTreeMap::new(),
+            revision: workflow.revision().map(String::from),
+            jobs: lock(jobs.iter().map(Job::fragment)),
+            steps: lock(jobs.iter().flat_map(Job::steps)),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Locks the libraries to the commits that the fragments were read from, by library name
+    pub fn with_commits(mut self, commits: BTreeMap<String, String>) -> Self {
+        self.commits = commits;
+        self
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the commits that the libraries are locked to, by library name
+    pub fn commits(&self) -> &BTreeMap<String, String> {
+        &self.commits
+    }
+
+    pub fn revision(&self) -> Option<&str> {
+        self.revision.as_deref()
+    }
+
+    pub fn jobs(&self) -> &[LockedFragment] {
+        &self.jobs
+    }
+
+    pub fn steps(&self) -> &[LockedFragment] {
+        &self.steps
+    }
+
+    /// Checks that the workflow was resolved to the same revisions as the locked workflow
+    pub fn verify(&self, locked: Option<&LockedWorkflow>) -> Result<(), Error> {
+        let Some(locked) = locked else {
+            return Err(anyhow!(
+                "workflow '{}' is missing from {LOCKFILE_NAME}",
+                self.name
+            ));
+        };
+
+        let mut drifted = Vec::new();
+
+        if self.source != locked.source {
+            drifted.push(format!(
+                "library changed from '{}' to '{}'",
+                locked.source, self.source
+            ));
+        }
+        for (library, commit) in &self.commits {
+            match locked.commits.get(library) {
+                Some(locked_commit) if locked_commit == commit => {}
+                Some(locked_commit) => drifted.push(format!(
+                    "library '{library}' moved from {locked_commit} to {commit}"
+                )),
+                None => drifted.push(format!("library '{library}' is not locked")),
+            }
+        }
+        if self.revision != locked.revision {
+            drifted.push(format!("workflow '{}' changed", self.name));
+        }
+
+        for (kind, fragments, locked_fragments) in [
+            ("job", &self.jobs, &locked.jobs),
+            ("step", &self.steps, &locked.steps),
+        ] {
+            for fragment in fragments {
+                match locked_fragments
+                    .iter()
+                    .find(|locked_fragment| locked_fragment.name == fragment.name)
+                {
+                    Some(locked_fragment) if locked_fragment.revision == fragment.revision => {}
+                    Some(_) => drifted.push(format!("{kind} '{}' changed", fragment.name)),
+                    None => drifted.push(format!("{kind} '{}' is not locked", fragment.name)),
+                }
+            }
+        }
+
+        if drifted.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "templates for workflow '{}' differ from {LOCKFILE_NAME}: {}",
+                self.name,
+                drifted.join(", ")
+            ))
+        }
+    }
+}
+
+/// Locks the revisions of fragments, locking fragments that are used more than once only once
+fn lock<'a>(fragments: impl Iterator<Item = &'a Fragment>) -> Vec<LockedFragment> {
+    let mut locked_fragments = Vec::new();
+
+    for fragment in fragments {
+        let locked_fragment = LockedFragment::from(fragment);
+        if !locked_fragments.contains(&locked_fragment) {
+            locked_fragments.push(locked_fragment);
+        }
+    }
+
+    locked_fragments
+}
+
+/// The revision of a job or step fragment
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Deserialize, Serialize)]
+pub struct LockedFragment {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    revision: Option<String>,
+}
+
+impl LockedFragment {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn revision(&self) -> Option<&str> {
+        self.revision.as_deref()
+    }
+}
+
+impl From<&Fragment> for LockedFragment {
+    fn from(fragment: &Fragment) -> Self {
+        Self {
+            name: fragment.name().into(),
+            revision: fragment.revision().map(String::from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TestProject;
+
+    use super::*;
+
+    fn fragment(name: &str, revision: &str) -> Fragment {
+        Fragment::builder()
+            .name(name)
+            .template("".into())
+            .revision(revision)
+            .build()
+    }
+
+    fn locked_workflow(job_revision: &str) -> LockedWorkflow {
+        LockedWorkflow::new(
+            "github jdno/workflows",
+            &fragment("rust", "a"),
+            &[fragment("lint", job_revision).into()],
+        )
+    }
+
+    #[test]
+    fn load_returns_empty_lockfile_if_not_exists() {
+        let test_project = TestProject::new().unwrap();
+
+        let lockfile = Lockfile::load(test_project.project()).unwrap();
+
+        assert!(lockfile.workflows().is_empty());
+    }
+
+    #[test]
+    fn save_writes_lockfile() {
+        let test_project = TestProject::new().unwrap();
+
+        let mut lockfile = Lockfile::default();
+        lockfile.add_workflow(locked_workflow("b"));
+        lockfile.save(test_project.project()).unwrap();
+
+        let loaded_lockfile = Lockfile::load(test_project.project()).unwrap();
+        assert_eq!(lockfile, loaded_lockfile);
+    }
+
+    #[test]
+    fn add_workflow_replaces_existing_workflow() {
+        let mut lockfile = Lockfile::default();
+
+        lockfile.add_workflow(locked_workflow("b"));
+        lockfile.add_workflow(locked_workflow("c"));
+
+        assert_eq!(1, lockfile.workflows().len());
+        assert_eq!(
+            Some("c"),
+            lockfile.workflow("rust").unwrap().jobs()[0].revision()
+        );
+    }
+
+    #[test]
+    fn remove_workflow() {
+        let mut lockfile = Lockfile::default();
+        lockfile.add_workflow(locked_workflow("b"));
+
+        lockfile.remove_workflow("rust");
+
+        assert!(lockfile.workflows().is_empty());
+    }
+
+    #[test]
+    fn verify_with_same_revisions() {
+        let workflow = locked_workflow("b");
+
+        assert!(workflow.verify(Some(&locked_workflow("b"))).is_ok());
+    }
+
+    #[test]
+    fn verify_with_changed_job() {
+        let workflow = locked_workflow("c");
+
+        let error = workflow.verify(Some(&locked_workflow("b"))).unwrap_err();
+
+        assert_eq!(
+            "templates for workflow 'rust' differ from flowcrafter.lock: job 'lint' changed",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn verify_with_moved_library() {
+        let commits = |commit: &str| BTreeMap::from([("default".into(), commit.into())]);
+        let workflow = locked_workflow("b").with_commits(commits("def"));
+        let locked = locked_workflow("b").with_commits(commits("abc"));
+
+        let error = workflow.verify(Some(&locked)).unwrap_err();
+
+        assert_eq!(
+            "templates for workflow 'rust' differ from flowcrafter.lock: library 'default' moved \
+             from abc to def",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn verify_with_changed_step() {
+        let job = |revision: &str| {
+            Job::builder()
+                .fragment(fragment("lint", "b"))
+                .steps(vec![fragment("setup", revision)])
+                .build()
+        };
+        let workflow =
+            LockedWorkflow::new("github jdno/workflows", &fragment("rust", "a"), &[job("d")]);
+        let locked =
+            LockedWorkflow::new("github jdno/workflows", &fragment("rust", "a"), &[job("c")]);
+
+        let error = workflow.verify(Some(&locked)).unwrap_err();
+
+        assert_eq!(
+            "templates for workflow 'rust' differ from flowcrafter.lock: step 'setup' changed",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn verify_without_locked_workflow() {
+        let workflow = locked_workflow("b");
+
+        let error = workflow.verify(None).unwrap_err();
+
+        assert_eq!(
+            "workflow 'rust' is missing from flowcrafter.lock",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Lockfile>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Lockfile>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Lockfile>();
+    }
+}
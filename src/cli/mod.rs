@@ -2,14 +2,21 @@ use clap::Parser;
 
 pub use self::{
     commands::*,
-    configuration::{Configuration, LibraryConfiguration},
+    configuration::{Configuration, Libraries, LibraryConfiguration, NamedLibraryConfiguration},
+    lockfile::{LockedFragment, LockedWorkflow, Lockfile},
 };
 
 mod commands;
 mod configuration;
+mod diff;
+mod lockfile;
 
 #[derive(Clone, Debug, Parser)]
 pub struct Cli {
+    /// Read templates only from the cache instead of downloading them
+    #[arg(long, global = true)]
+    pub offline: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
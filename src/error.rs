@@ -1,17 +1,36 @@
 use thiserror::Error;
 
 use crate::fragment::FragmentError;
+use crate::validation::Violation;
 
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("failed to read archive: {0}")]
+    Archive(String),
+
+    #[error("failed to authenticate with GitHub: {0}")]
+    Authentication(String),
+
+    #[error("checksum of {0} does not match, expected sha256 {1} but got {2}")]
+    Checksum(String, String, String),
+
     #[error("failed to parse configuration: {0}")]
     Configuration(&'static str),
 
+    #[error("jobs depend on each other in a cycle: {}", .0.join(" -> "))]
+    DependencyCycle(Vec<String>),
+
+    #[error("job '{0}' is defined in both '{1}' and '{2}'")]
+    DuplicateJob(String, String, String),
+
     #[error("{0}")]
     Fragment(#[from] FragmentError),
 
+    #[error("failed to run git: {0}")]
+    Git(String),
+
     #[error("{0}")]
-    GitHub(#[from] octocrab::Error),
+    GitHub(Box<octocrab::Error>),
 
     #[error("{0}")]
     InvalidTemplate(String),
@@ -19,14 +38,43 @@ pub enum Error {
     #[error("{0}")]
     IO(#[from] std::io::Error),
 
+    #[error("missing value for variable '{1}' in fragment '{0}'")]
+    MissingVariable(String, String),
+
     #[error("failed to find '{0}' in {1}")]
     NotFound(String, String),
 
+    #[error("'{0}' from {1} is not cached, run FlowCrafter without --offline to download it")]
+    NotCached(String, String),
+
     #[error("failed to render workflow: {0}")]
     Render(String),
 
+    #[error("library '{0}' is not configured")]
+    UnknownLibrary(String),
+
+    #[error("failed to find reference '{0}' in {1}")]
+    UnknownReference(String, String),
+
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
+
+    #[error("{0} is not a valid workflow:{}", list(.1))]
+    Validation(String, Vec<Violation>),
+}
+
+/// Formats violations as an indented list, with one violation per line
+fn list(violations: &[Violation]) -> String {
+    violations
+        .iter()
+        .map(|violation| format!("\n  {violation}"))
+        .collect()
+}
+
+impl From<octocrab::Error> for Error {
+    fn from(error: octocrab::Error) -> Self {
+        Self::GitHub(Box::new(error))
+    }
 }
 
 #[cfg(test)]
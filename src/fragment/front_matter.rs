@@ -0,0 +1,140 @@
+use serde_yaml::Value;
+
+use crate::error::Error;
+
+/// Splits a fragment into its optional front matter and its template
+///
+/// Fragments can declare metadata in a YAML document that precedes the template in the same file.
+/// A fragment has front matter when the file contains two YAML documents, in which case the first
+/// one is the front matter and the second one the template.
+pub fn split(content: &str) -> Result<(Option<Value>, &str), Error> {
+    let Some(start) = template_start(content) else {
+        return Ok((None, content));
+    };
+
+    let front_matter = serde_yaml::from_str(&content[..start]).map_err(|error| {
+        Error::InvalidTemplate(format!("failed to parse front matter: {error}"))
+    })?;
+
+    Ok((Some(front_matter), &content[start..]))
+}
+
+/// Parses the metadata in a sidecar file and merges it with the front matter of a fragment
+///
+/// A key that is declared in both places is taken from the front matter.
+pub fn merge(sidecar: Option<&str>, front_matter: Option<Value>) -> Result<Option<Value>, Error> {
+    let Some(sidecar) = sidecar else {
+        return Ok(front_matter);
+    };
+
+    let sidecar: Value = serde_yaml::from_str(sidecar)
+        .map_err(|error| Error::InvalidTemplate(format!("failed to parse metadata: {error}")))?;
+
+    match (sidecar, front_matter) {
+        (Value::Mapping(mut sidecar), Some(Value::Mapping(front_matter))) => {
+            sidecar.extend(front_matter);
+            Ok(Some(Value::Mapping(sidecar)))
+        }
+        (Value::Mapping(sidecar), None | Some(Value::Null)) => Ok(Some(Value::Mapping(sidecar))),
+        (Value::Null, front_matter) => Ok(front_matter),
+        _ => Err(Error::InvalidTemplate(
+            "metadata must be a mapping of keys to values".into(),
+        )),
+    }
+}
+
+/// Returns the byte offset of the document marker that starts the second document
+fn template_start(content: &str) -> Option<usize> {
+    let mut offset = 0;
+    let mut seen_content = false;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+
+        if trimmed == "---" || trimmed.starts_with("--- ") {
+            if seen_content {
+                return Some(offset);
+            }
+        } else if !trimmed.is_empty() && !trimmed.starts_with('#') {
+            seen_content = true;
+        }
+
+        offset += line.len();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn split_without_front_matter() {
+        let content = indoc!(
+            r#"
+            ---
+            name: Workflow
+            "#
+        );
+
+        let (front_matter, template) = split(content).unwrap();
+
+        assert!(front_matter.is_none());
+        assert_eq!(content, template);
+    }
+
+    #[test]
+    fn split_with_front_matter() {
+        let content = indoc!(
+            r#"
+            ---
+            variables:
+              toolchain: stable
+            ---
+            name: Workflow
+            "#
+        );
+
+        let (front_matter, template) = split(content).unwrap();
+
+        assert!(front_matter.unwrap().get("variables").is_some());
+        assert_eq!("---\nname: Workflow\n", template);
+    }
+
+    #[test]
+    fn merge_prefers_front_matter() {
+        let front_matter = serde_yaml::from_str("description: Front matter\n").unwrap();
+
+        let metadata = merge(
+            Some("description: Sidecar\nauthor: jdno\n"),
+            Some(front_matter),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(Some("Front matter"), metadata["description"].as_str());
+        assert_eq!(Some("jdno"), metadata["author"].as_str());
+    }
+
+    #[test]
+    fn merge_without_sidecar() {
+        assert!(merge(None, None).unwrap().is_none());
+    }
+
+    #[test]
+    fn merge_errors_on_invalid_sidecar() {
+        let error = merge(Some("- description\n"), None).unwrap_err();
+
+        assert!(matches!(error, Error::InvalidTemplate(_)));
+    }
+
+    #[test]
+    fn split_with_invalid_front_matter() {
+        let error = split("---\nvariables: [\n---\nname: Workflow\n").unwrap_err();
+
+        assert!(matches!(error, Error::InvalidTemplate(_)));
+    }
+}
@@ -0,0 +1,387 @@
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+
+use crate::error::Error;
+use crate::fragment::{Fragment, FragmentLibrary};
+
+/// A library and the name that its fragments can be referenced with
+pub type Layer = (String, Box<dyn for<'a> FragmentLibrary<'a>>);
+
+/// A stack of named libraries that are searched in the order of their precedence
+///
+/// Fragments can be referenced with the name of a library, e.g. `org:rust` for a workflow or
+/// `org:rust/test` for a job, in which case only that library is searched. Unqualified fragments
+/// are read from the first library that contains them, which allows a library to override single
+/// fragments of the libraries after it.
+pub struct LayeredLibrary {
+    layers: Vec<Layer>,
+}
+
+impl LayeredLibrary {
+    pub fn new(layers: Vec<Layer>) -> Self {
+        Self { layers }
+    }
+
+    /// Returns the layers that a reference must be searched in
+    fn layers(&self, library: Option<&str>) -> Result<Vec<&Layer>, Error> {
+        match library {
+            Some(library) => self
+                .layers
+                .iter()
+                .find(|(name, _)| name == library)
+                .map(|layer| vec![layer])
+                .ok_or_else(|| Error::UnknownLibrary(library.into())),
+            None => Ok(self.layers.iter().collect()),
+        }
+    }
+}
+
+impl Display for LayeredLibrary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let layers: Vec<String> = self
+            .layers
+            .iter()
+            .map(|(name, library)| format!("{name}: {library}"))
+            .collect();
+
+        write!(f, "{}", layers.join(", "))
+    }
+}
+
+#[async_trait]
+impl<'a> FragmentLibrary<'a> for LayeredLibrary {
+    async fn workflow(&self, name: &'a str) -> Result<Fragment, Error> {
+        let (library, workflow) = split_reference(name);
+
+        for (_, layer) in self.layers(library)? {
+            match layer.workflow(workflow).await {
+                Err(Error::NotFound(_, _)) => continue,
+                result => return result,
+            }
+        }
+
+        Err(Error::NotFound(name.into(), self.to_string()))
+    }
+
+    async fn job(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error> {
+        let (library, job) = split_reference(name);
+        let (workflow, job) = match job.split_once('/') {
+            Some((workflow, job)) => (workflow, job),
+            None => (split_reference(workflow).1, job),
+        };
+
+        for (_, layer) in self.layers(library)? {
+            match layer.job(workflow, job).await {
+                Err(Error::NotFound(_, _)) => continue,
+                result => return result,
+            }
+        }
+
+        Err(Error::NotFound(name.into(), self.to_string()))
+    }
+
+    async fn workflows(&self) -> Result<Vec<String>, Error> {
+        let mut workflows = Vec::new();
+
+        for (_, layer) in &self.layers {
+            workflows.extend(layer.workflows().await?);
+        }
+
+        workflows.sort();
+        workflows.dedup();
+
+        Ok(workflows)
+    }
+
+    async fn jobs(&self, workflow: &'a str) -> Result<Vec<String>, Error> {
+        let (library, workflow_name) = split_reference(workflow);
+
+        let mut jobs = Vec::new();
+        let mut found = false;
+
+        for (_, layer) in self.layers(library)? {
+            match layer.jobs(workflow_name).await {
+                Ok(layer_jobs) => {
+                    jobs.extend(layer_jobs);
+                    found = true;
+                }
+                Err(Error::NotFound(_, _)) => continue,
+                Err(error) => return Err(error),
+            }
+        }
+
+        if !found {
+            return Err(Error::NotFound(workflow.into(), self.to_string()));
+        }
+
+        jobs.sort();
+        jobs.dedup();
+
+        Ok(jobs)
+    }
+
+    async fn step(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error> {
+        let (library, step) = split_reference(name);
+        let workflow = split_reference(workflow).1;
+
+        for (_, layer) in self.layers(library)? {
+            match layer.step(workflow, step).await {
+                Err(Error::NotFound(_, _)) => continue,
+                result => return result,
+            }
+        }
+
+        Err(Error::NotFound(name.into(), self.to_string()))
+    }
+
+    async fn commit(&self, library: Option<&'a str>) -> Result<Option<String>, Error> {
+        let Some(library) = library else {
+            return Ok(None);
+        };
+
+        match self.layers(Some(library))?.first() {
+            Some((_, layer)) => layer.commit(None).await,
+            None => Ok(None),
+        }
+    }
+}
+
+/// Splits a reference like `org:rust` into the name of the library and the name of the fragment
+pub(crate) fn split_reference(reference: &str) -> (Option<&str>, &str) {
+    match reference.split_once(':') {
+        Some((library, name)) => (Some(library), name),
+        None => (None, reference),
+    }
+}
+
+/// Returns the name of the job that a reference points to, e.g. `test` for `org:rust/test`
+pub(crate) fn job_reference_name(reference: &str) -> &str {
+    split_reference(reference)
+        .1
+        .rsplit('/')
+        .next()
+        .unwrap_or(reference)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::{tempdir, TempDir};
+
+    use crate::local::LocalLibrary;
+
+    use super::*;
+
+    /// Creates a library with the `rust` workflow and the given jobs
+    fn layer(jobs: &[(&str, &str)]) -> TempDir {
+        let directory = tempdir().unwrap();
+        let workflow = directory.path().join("rust");
+
+        std::fs::create_dir(&workflow).unwrap();
+        std::fs::write(workflow.join("workflow.yml"), "name: Rust\n").unwrap();
+
+        for (name, runner) in jobs {
+            std::fs::write(
+                workflow.join(format!("{name}.yml")),
+                format!("{name}:\n  runs-on: {runner}\n"),
+            )
+            .unwrap();
+        }
+
+        directory
+    }
+
+    fn library(org: &TempDir, local: &TempDir) -> LayeredLibrary {
+        LayeredLibrary::new(vec![
+            ("local".into(), Box::new(LocalLibrary::at(local.path()))),
+            ("org".into(), Box::new(LocalLibrary::at(org.path()))),
+        ])
+    }
+
+    #[tokio::test]
+    async fn job_from_library_with_precedence() {
+        let org = layer(&[("lint", "ubuntu-latest"), ("test", "ubuntu-latest")]);
+        let local = layer(&[("test", "self-hosted")]);
+
+        let job = library(&org, &local).job("rust", "test").await.unwrap();
+
+        assert!(job.template().get().contains("self-hosted"));
+    }
+
+    #[tokio::test]
+    async fn job_falls_through_to_next_library() {
+        let org = layer(&[("lint", "ubuntu-latest")]);
+        let local = layer(&[]);
+
+        let job = library(&org, &local).job("rust", "lint").await.unwrap();
+
+        assert!(job.template().get().contains("ubuntu-latest"));
+    }
+
+    #[tokio::test]
+    async fn job_with_qualified_reference() {
+        let org = layer(&[("test", "ubuntu-latest")]);
+        let local = layer(&[("test", "self-hosted")]);
+
+        let job = library(&org, &local)
+            .job("rust", "org:rust/test")
+            .await
+            .unwrap();
+
+        assert!(job.template().get().contains("ubuntu-latest"));
+    }
+
+    #[tokio::test]
+    async fn job_not_found() {
+        let org = layer(&[]);
+        let local = layer(&[]);
+
+        let error = library(&org, &local).job("rust", "test").await.unwrap_err();
+
+        assert!(matches!(error, Error::NotFound(name, _) if name == "test"));
+    }
+
+    /// A library whose reference does not exist
+    struct MissingReference;
+
+    impl Display for MissingReference {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "repository org/workflows@v2")
+        }
+    }
+
+    #[async_trait]
+    impl<'a> FragmentLibrary<'a> for MissingReference {
+        async fn workflow(&self, _name: &'a str) -> Result<Fragment, Error> {
+            Err(Error::UnknownReference("v2".into(), self.to_string()))
+        }
+
+        async fn job(&self, _workflow: &'a str, _name: &'a str) -> Result<Fragment, Error> {
+            Err(Error::UnknownReference("v2".into(), self.to_string()))
+        }
+
+        async fn workflows(&self) -> Result<Vec<String>, Error> {
+            Err(Error::UnknownReference("v2".into(), self.to_string()))
+        }
+
+        async fn jobs(&self, _workflow: &'a str) -> Result<Vec<String>, Error> {
+            Err(Error::UnknownReference("v2".into(), self.to_string()))
+        }
+
+        async fn step(&self, _workflow: &'a str, _name: &'a str) -> Result<Fragment, Error> {
+            Err(Error::UnknownReference("v2".into(), self.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn job_does_not_fall_through_unknown_reference() {
+        let org = layer(&[("test", "ubuntu-latest")]);
+        let library = LayeredLibrary::new(vec![
+            ("shared".into(), Box::new(MissingReference)),
+            ("org".into(), Box::new(LocalLibrary::at(org.path()))),
+        ]);
+
+        let error = library.job("rust", "test").await.unwrap_err();
+
+        assert!(matches!(error, Error::UnknownReference(reference, _) if reference == "v2"));
+    }
+
+    #[tokio::test]
+    async fn job_from_unknown_library() {
+        let org = layer(&[]);
+        let local = layer(&[]);
+
+        let error = library(&org, &local)
+            .job("rust", "shared:rust/test")
+            .await
+            .unwrap_err();
+
+        assert_eq!("library 'shared' is not configured", error.to_string());
+    }
+
+    #[tokio::test]
+    async fn workflow_with_qualified_reference() {
+        let org = layer(&[]);
+        let local = layer(&[]);
+
+        let workflow = library(&org, &local).workflow("org:rust").await.unwrap();
+
+        assert_eq!("rust", workflow.name());
+    }
+
+    #[tokio::test]
+    async fn jobs_of_all_libraries() {
+        let org = layer(&[("lint", "ubuntu-latest"), ("test", "ubuntu-latest")]);
+        let local = layer(&[("style", "ubuntu-latest"), ("test", "self-hosted")]);
+
+        let jobs = library(&org, &local).jobs("rust").await.unwrap();
+
+        assert_eq!(vec!["lint", "style", "test"], jobs);
+    }
+
+    #[tokio::test]
+    async fn step_with_qualified_reference() {
+        let org = layer(&[]);
+        let local = layer(&[]);
+
+        for (directory, runner) in [(&org, "org"), (&local, "local")] {
+            std::fs::create_dir(directory.path().join("steps")).unwrap();
+            std::fs::write(
+                directory.path().join("steps/setup.yml"),
+                format!("- run: echo {runner}\n"),
+            )
+            .unwrap();
+        }
+
+        let library = library(&org, &local);
+        let unqualified = library.step("rust", "setup").await.unwrap();
+        let qualified = library.step("rust", "org:setup").await.unwrap();
+
+        assert!(unqualified.template().get().contains("local"));
+        assert!(qualified.template().get().contains("org"));
+    }
+
+    #[test]
+    fn split_reference_with_library() {
+        assert_eq!((Some("org"), "rust/test"), split_reference("org:rust/test"));
+    }
+
+    #[test]
+    fn split_reference_without_library() {
+        assert_eq!((None, "test"), split_reference("test"));
+    }
+
+    #[test]
+    fn job_reference_name_strips_library_and_workflow() {
+        assert_eq!("test", job_reference_name("org:rust/test"));
+        assert_eq!("test", job_reference_name("test"));
+    }
+
+    #[test]
+    fn trait_display() {
+        let library = LayeredLibrary::new(vec![(
+            "local".into(),
+            Box::new(LocalLibrary::at("templates")),
+        )]);
+
+        assert_eq!("local: path templates", library.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<LayeredLibrary>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<LayeredLibrary>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<LayeredLibrary>();
+    }
+}
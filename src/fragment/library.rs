@@ -9,4 +9,82 @@ use crate::fragment::Fragment;
 pub trait FragmentLibrary<'a>: Display + Send + Sync {
     async fn workflow(&self, name: &'a str) -> Result<Fragment, Error>;
     async fn job(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error>;
+
+    /// Returns the names of the workflows in the library in alphabetical order
+    async fn workflows(&self) -> Result<Vec<String>, Error>;
+
+    /// Returns the names of the jobs for a workflow in alphabetical order
+    async fn jobs(&self, workflow: &'a str) -> Result<Vec<String>, Error>;
+
+    /// Returns a snippet of steps that job templates can reference with `- step: <name>`
+    ///
+    /// Steps are searched in the `steps` folder of the workflow first, and then in the `steps`
+    /// folder at the root of the library, which is shared by all workflows.
+    async fn step(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error>;
+
+    /// Returns the commit that fragments are read from, for libraries in a repository
+    ///
+    /// Libraries that combine other libraries return the commit of the library with the given
+    /// name, while all other libraries ignore the name.
+    async fn commit(&self, _library: Option<&'a str>) -> Result<Option<String>, Error> {
+        Ok(None)
+    }
+}
+
+/// Returns the name of the job that is defined in a file, or `None` if the file is not a job
+pub(crate) fn job_name(file_name: &str) -> Option<&str> {
+    file_name
+        .strip_suffix(".yml")
+        .filter(|name| !name.is_empty() && *name != "workflow" && !name.ends_with(".meta"))
+}
+
+/// Returns the path of the sidecar file with the metadata of a fragment, e.g. `rust/test.meta.yml`
+pub(crate) fn sidecar_path(path: &str) -> String {
+    format!("{}.meta.yml", path.strip_suffix(".yml").unwrap_or(path))
+}
+
+/// Returns the paths at which a step is searched, e.g. `rust/steps/setup.yml` and `steps/setup.yml`
+pub(crate) fn step_paths(workflow: &str, name: &str) -> [String; 2] {
+    [
+        format!("{workflow}/steps/{name}.yml"),
+        format!("steps/{name}.yml"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_name_strips_extension() {
+        assert_eq!(Some("lint"), job_name("lint.yml"));
+    }
+
+    #[test]
+    fn job_name_skips_workflow() {
+        assert_eq!(None, job_name("workflow.yml"));
+    }
+
+    #[test]
+    fn job_name_skips_sidecars() {
+        assert_eq!(None, job_name("lint.meta.yml"));
+    }
+
+    #[test]
+    fn sidecar_path_replaces_extension() {
+        assert_eq!("rust/lint.meta.yml", sidecar_path("rust/lint.yml"));
+    }
+
+    #[test]
+    fn step_paths_search_workflow_first() {
+        assert_eq!(
+            ["rust/steps/setup.yml", "steps/setup.yml"],
+            step_paths("rust", "setup")
+        );
+    }
+
+    #[test]
+    fn job_name_skips_other_files() {
+        assert_eq!(None, job_name("README.md"));
+    }
 }
@@ -0,0 +1,159 @@
+use serde_yaml::Value;
+
+use crate::error::Error;
+
+/// Information about a fragment that is declared in its front matter or sidecar file
+///
+/// None of the keys are required. A job that does not list any compatible workflows can be used
+/// with every workflow.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Metadata {
+    description: Option<String>,
+    author: Option<String>,
+    secrets: Vec<String>,
+    workflows: Vec<String>,
+    requires: Vec<String>,
+}
+
+impl Metadata {
+    /// Parses the metadata from a fragment's front matter, ignoring keys that it does not know
+    pub(crate) fn parse(front_matter: Option<&Value>) -> Result<Self, Error> {
+        let Some(front_matter) = front_matter else {
+            return Ok(Self::default());
+        };
+
+        Ok(Self {
+            description: string(front_matter, "description")?,
+            author: string(front_matter, "author")?,
+            secrets: strings(front_matter, "secrets")?,
+            workflows: strings(front_matter, "workflows")?,
+            requires: strings(front_matter, "requires")?,
+        })
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// Returns the secrets that must be configured in the repository for the fragment to work
+    pub fn secrets(&self) -> &[String] {
+        &self.secrets
+    }
+
+    /// Returns the workflows that a job is compatible with, or an empty list for all workflows
+    pub fn workflows(&self) -> &[String] {
+        &self.workflows
+    }
+
+    /// Returns the jobs that a job depends on, which are added to the workflow together with it
+    pub fn requires(&self) -> &[String] {
+        &self.requires
+    }
+
+    pub fn is_compatible_with(&self, workflow: &str) -> bool {
+        self.workflows.is_empty() || self.workflows.iter().any(|name| name == workflow)
+    }
+}
+
+fn string(front_matter: &Value, key: &str) -> Result<Option<String>, Error> {
+    match front_matter.get(key) {
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(Value::Null) | None => Ok(None),
+        Some(_) => Err(Error::InvalidTemplate(format!("{key} must be a string"))),
+    }
+}
+
+fn strings(front_matter: &Value, key: &str) -> Result<Vec<String>, Error> {
+    let invalid = || Error::InvalidTemplate(format!("{key} must be a list of strings"));
+
+    match front_matter.get(key) {
+        Some(Value::Sequence(values)) => values
+            .iter()
+            .map(|value| value.as_str().map(String::from).ok_or_else(invalid))
+            .collect(),
+        Some(Value::Null) | None => Ok(Vec::new()),
+        Some(_) => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn parse(yaml: &str) -> Result<Metadata, Error> {
+        let front_matter: Value = serde_yaml::from_str(yaml).unwrap();
+        Metadata::parse(Some(&front_matter))
+    }
+
+    #[test]
+    fn parse_all_keys() {
+        let metadata = parse(indoc!(
+            r#"
+            description: Run the tests
+            author: jdno
+            secrets:
+              - CODECOV_TOKEN
+            workflows:
+              - rust
+            requires:
+              - build
+            "#
+        ))
+        .unwrap();
+
+        assert_eq!(Some("Run the tests"), metadata.description());
+        assert_eq!(Some("jdno"), metadata.author());
+        assert_eq!(vec!["CODECOV_TOKEN"], metadata.secrets());
+        assert_eq!(vec!["rust"], metadata.workflows());
+        assert_eq!(vec!["build"], metadata.requires());
+    }
+
+    #[test]
+    fn parse_without_front_matter() {
+        assert_eq!(Metadata::default(), Metadata::parse(None).unwrap());
+    }
+
+    #[test]
+    fn parse_errors_on_invalid_secrets() {
+        let error = parse("secrets: CODECOV_TOKEN\n").unwrap_err();
+
+        assert_eq!("secrets must be a list of strings", error.to_string());
+    }
+
+    #[test]
+    fn is_compatible_with_listed_workflow() {
+        let metadata = parse("workflows: [rust]\n").unwrap();
+
+        assert!(metadata.is_compatible_with("rust"));
+        assert!(!metadata.is_compatible_with("markdown"));
+    }
+
+    #[test]
+    fn is_compatible_with_all_workflows_by_default() {
+        assert!(Metadata::default().is_compatible_with("markdown"));
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Metadata>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Metadata>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Metadata>();
+    }
+}
@@ -1,23 +1,89 @@
 use std::fmt::{Display, Formatter};
 
+use serde_yaml::Value;
 use typed_builder::TypedBuilder;
 
+use crate::error::Error;
 use crate::template::Template;
+use crate::variable::Variable;
 
 pub use self::error::FragmentError;
+pub use self::layered::{Layer, LayeredLibrary};
 pub use self::library::FragmentLibrary;
+pub use self::metadata::Metadata;
+
+pub(crate) use self::layered::job_reference_name;
+#[cfg(feature = "cli")]
+pub(crate) use self::layered::split_reference;
+pub(crate) use self::library::{job_name, sidecar_path, step_paths};
 
 mod error;
+mod front_matter;
+mod layered;
 mod library;
+mod metadata;
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, TypedBuilder)]
 pub struct Fragment {
     #[builder(setter(into))]
     name: String,
     template: Template,
+    #[builder(default)]
+    variables: Vec<Variable>,
+    #[builder(default)]
+    metadata: Metadata,
+    #[builder(default, setter(into, strip_option))]
+    revision: Option<String>,
 }
 
 impl Fragment {
+    /// Parses a fragment and the variables and metadata that are declared in its front matter
+    pub fn parse(name: impl Into<String>, content: &str) -> Result<Self, Error> {
+        Self::parse_with_sidecar(name, content, None)
+    }
+
+    /// Parses a fragment whose metadata can also be declared in a sidecar file, e.g. `test.meta.yml`
+    ///
+    /// Keys in the front matter of the fragment take precedence over the keys in the sidecar.
+    pub fn parse_with_sidecar(
+        name: impl Into<String>,
+        content: &str,
+        sidecar: Option<&str>,
+    ) -> Result<Self, Error> {
+        let (front_matter, template) = front_matter::split(content)?;
+        let front_matter = front_matter::merge(sidecar, front_matter)?;
+
+        let variables = match front_matter
+            .as_ref()
+            .and_then(|value| value.get("variables"))
+        {
+            Some(Value::Mapping(variables)) => variables
+                .iter()
+                .map(|(name, declaration)| {
+                    let name = name.as_str().ok_or_else(|| {
+                        Error::InvalidTemplate("variable names must be strings".into())
+                    })?;
+
+                    Variable::parse(name, declaration)
+                })
+                .collect::<Result<Vec<Variable>, Error>>()?,
+            Some(Value::Null) | None => Vec::new(),
+            Some(_) => {
+                return Err(Error::InvalidTemplate(
+                    "variables must be a mapping from names to declarations".into(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            name: name.into(),
+            template: Template::new(template),
+            variables,
+            metadata: Metadata::parse(front_matter.as_ref())?,
+            revision: None,
+        })
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -25,6 +91,70 @@ impl Fragment {
     pub fn template(&self) -> &Template {
         &self.template
     }
+
+    pub fn set_template(&mut self, template: Template) {
+        self.template = template;
+    }
+
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Returns the names of the steps that the jobs in the template reference with `- step: <name>`
+    ///
+    /// Only items in the `steps` of a job are references, so that a line like `- step: <name>`
+    /// inside a multi-line string, e.g. a `run` script, is not mistaken for one.
+    pub fn steps(&self) -> Result<Vec<String>, Error> {
+        let jobs: Value = serde_yaml::from_str(self.template.get()).map_err(|error| {
+            Error::InvalidTemplate(format!("failed to parse '{}': {error}", self.name))
+        })?;
+
+        Ok(step_references(&jobs)
+            .into_iter()
+            .map(String::from)
+            .collect())
+    }
+
+    /// Returns the revision of the fragment in its library, e.g. a commit or a content hash
+    pub fn revision(&self) -> Option<&str> {
+        self.revision.as_deref()
+    }
+
+    pub fn set_revision(&mut self, revision: impl Into<String>) {
+        self.revision = Some(revision.into());
+    }
+}
+
+/// Returns the names of the steps that are referenced in the `steps` of the jobs in a fragment
+pub(crate) fn step_references(jobs: &Value) -> Vec<&str> {
+    let mut references = Vec::new();
+
+    let steps = jobs
+        .as_mapping()
+        .into_iter()
+        .flat_map(|jobs| jobs.values())
+        .filter_map(|job| job.get("steps").and_then(Value::as_sequence))
+        .flatten();
+
+    for reference in steps.filter_map(step_reference) {
+        if !references.contains(&reference) {
+            references.push(reference);
+        }
+    }
+
+    references
+}
+
+/// Returns the name of the step that an item in `steps` references, e.g. `setup` for `step: setup`
+pub(crate) fn step_reference(step: &Value) -> Option<&str> {
+    match step.as_mapping() {
+        Some(step) if step.len() == 1 => step.get("step").and_then(Value::as_str),
+        _ => None,
+    }
 }
 
 impl Display for Fragment {
@@ -35,18 +165,116 @@ impl Display for Fragment {
 
 #[cfg(test)]
 mod tests {
+    use indoc::indoc;
+
     use super::*;
 
     const NAME: &str = "test";
     const TEMPLATE: &str = "{{template}}";
+    const REVISION: &str = "3d21ec53a331a6f037a91c368710b99387d012c1";
 
     fn fragment() -> Fragment {
         Fragment {
             name: NAME.to_string(),
             template: Template::new(TEMPLATE),
+            variables: Vec::new(),
+            metadata: Metadata::default(),
+            revision: Some(REVISION.to_string()),
         }
     }
 
+    #[test]
+    fn revision() {
+        let fragment = fragment();
+
+        assert_eq!(Some(REVISION), fragment.revision());
+    }
+
+    #[test]
+    fn parse_without_front_matter() {
+        let fragment = Fragment::parse(NAME, "name: Workflow\n").unwrap();
+
+        assert_eq!("name: Workflow\n", fragment.template().get());
+        assert!(fragment.variables().is_empty());
+    }
+
+    #[test]
+    fn parse_with_variables() {
+        let content = indoc!(
+            r#"
+            ---
+            variables:
+              toolchain: stable
+              directory:
+            ---
+            test:
+              runs-on: {{ runner }}
+            "#
+        );
+
+        let fragment = Fragment::parse(NAME, content).unwrap();
+
+        assert_eq!(
+            "---\ntest:\n  runs-on: {{ runner }}\n",
+            fragment.template().get()
+        );
+        assert_eq!(
+            vec![
+                Variable::builder()
+                    .name("toolchain")
+                    .default("stable")
+                    .build(),
+                Variable::builder().name("directory").build(),
+            ],
+            fragment.variables()
+        );
+    }
+
+    #[test]
+    fn parse_with_metadata() {
+        let content = indoc!(
+            r#"
+            ---
+            description: Run the tests
+            workflows: [rust]
+            ---
+            test:
+              runs-on: ubuntu-latest
+            "#
+        );
+
+        let fragment = Fragment::parse(NAME, content).unwrap();
+
+        assert_eq!(Some("Run the tests"), fragment.metadata().description());
+        assert!(!fragment.metadata().is_compatible_with("markdown"));
+    }
+
+    #[test]
+    fn parse_with_sidecar() {
+        let fragment = Fragment::parse_with_sidecar(
+            NAME,
+            "test:\n  runs-on: ubuntu-latest\n",
+            Some("author: jdno\nvariables:\n  toolchain: stable\n"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            "test:\n  runs-on: ubuntu-latest\n",
+            fragment.template().get()
+        );
+        assert_eq!(Some("jdno"), fragment.metadata().author());
+        assert_eq!("toolchain", fragment.variables()[0].name());
+    }
+
+    #[test]
+    fn parse_errors_on_invalid_variables() {
+        let content = "---\nvariables: [toolchain]\n---\nname: Workflow\n";
+
+        let error = Fragment::parse(NAME, content).unwrap_err();
+
+        assert!(matches!(error, Error::InvalidTemplate(_)));
+    }
+
     #[test]
     fn name() {
         let fragment = fragment();
@@ -61,6 +289,50 @@ mod tests {
         assert_eq!(TEMPLATE, fragment.template().get());
     }
 
+    #[test]
+    fn steps_returns_references() {
+        let fragment = Fragment::parse(
+            "test",
+            indoc!(
+                r#"
+                test:
+                  steps:
+                    - step: checkout # Shared by all jobs
+                    - step: "org:setup-rust"
+                    - run: 'echo "- step: not a reference"'
+                    - step: checkout
+                "#
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec!["checkout", "org:setup-rust"],
+            fragment.steps().unwrap()
+        );
+    }
+
+    #[test]
+    fn steps_ignores_block_scalars() {
+        let fragment = Fragment::parse(
+            "test",
+            indoc!(
+                r#"
+                test:
+                  steps:
+                    - step: checkout
+                    - run: |
+                        cat <<EOF > config.yml
+                        - step: not-a-reference
+                        EOF
+                "#
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(vec!["checkout"], fragment.steps().unwrap());
+    }
+
     #[test]
     fn trait_send() {
         fn assert_send<T: Send>() {}
@@ -0,0 +1,127 @@
+use std::fmt::{Display, Formatter};
+
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, TypedBuilder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GitConfiguration {
+    #[builder(setter(into))]
+    url: String,
+
+    #[cfg_attr(feature = "serde", serde(rename = "ref"))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[builder(default, setter(into, strip_option))]
+    reference: Option<String>,
+}
+
+impl GitConfiguration {
+    /// Returns the URL of the repository, which can be anything that `git clone` accepts
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the branch, tag, or commit that templates are read from
+    ///
+    /// Templates are read from the default branch of the repository if no reference is set.
+    pub fn reference(&self) -> Option<&str> {
+        self.reference.as_deref()
+    }
+
+    pub fn set_reference(&mut self, reference: impl Into<String>) {
+        self.reference = Some(reference.into());
+    }
+}
+
+impl Display for GitConfiguration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "git: {}", self.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize() {
+        let yaml = indoc!(
+            r#"
+            ---
+            url: https://gitlab.com/jdno/workflows.git
+            "#
+        );
+
+        let configuration = serde_yaml::from_str::<GitConfiguration>(yaml).unwrap();
+
+        assert_eq!("https://gitlab.com/jdno/workflows.git", configuration.url());
+        assert_eq!(None, configuration.reference());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_reference() {
+        let yaml = indoc!(
+            r#"
+            ---
+            url: https://gitlab.com/jdno/workflows.git
+            ref: v1.0.0
+            "#
+        );
+
+        let configuration = serde_yaml::from_str::<GitConfiguration>(yaml).unwrap();
+
+        assert_eq!(Some("v1.0.0"), configuration.reference());
+    }
+
+    #[test]
+    fn trait_display() {
+        let configuration = GitConfiguration::builder()
+            .url("https://gitlab.com/jdno/workflows.git")
+            .build();
+
+        assert_eq!(
+            "git: https://gitlab.com/jdno/workflows.git",
+            configuration.to_string()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_serialize_with_reference() {
+        let configuration = GitConfiguration::builder()
+            .url("https://gitlab.com/jdno/workflows.git")
+            .reference("v1.0.0")
+            .build();
+
+        let yaml = indoc!(
+            r#"
+            url: https://gitlab.com/jdno/workflows.git
+            ref: v1.0.0
+            "#
+        );
+
+        assert_eq!(yaml, serde_yaml::to_string(&configuration).unwrap());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<GitConfiguration>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<GitConfiguration>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<GitConfiguration>();
+    }
+}
@@ -0,0 +1,481 @@
+use std::ffi::OsStr;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::process::Command;
+
+use async_trait::async_trait;
+use tokio::sync::OnceCell;
+
+use crate::cache::Cache;
+use crate::error::Error;
+use crate::fragment::{Fragment, FragmentLibrary};
+use crate::git::GitConfiguration;
+use crate::local::LocalLibrary;
+
+/// A library of fragments in an arbitrary git repository
+///
+/// The repository is cloned into the cache when the first fragment is read, or fetched again if it
+/// has been cloned before. The configured reference is then checked out, and fragments are served
+/// from the working tree. In offline mode, an existing clone is used without fetching it.
+///
+/// The `git` command-line client must be installed, and it is responsible for authentication.
+#[derive(Clone, Debug)]
+pub struct GitLibrary {
+    config: GitConfiguration,
+    cache: Cache,
+    offline: bool,
+    checkout: OnceCell<(LocalLibrary, String)>,
+}
+
+impl GitLibrary {
+    pub fn new(config: GitConfiguration, cache: Cache) -> Self {
+        Self {
+            config,
+            cache,
+            offline: false,
+            checkout: OnceCell::new(),
+        }
+    }
+
+    /// Uses the existing clone of the repository instead of fetching it
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    async fn checkout(&self) -> Result<&LocalLibrary, Error> {
+        Ok(&self.checkout_with_commit().await?.0)
+    }
+
+    /// Checks out the configured reference, and returns the commit that it was resolved to
+    async fn checkout_with_commit(&self) -> Result<&(LocalLibrary, String), Error> {
+        self.checkout
+            .get_or_try_init(|| async {
+                // Cloning a repository can take a while, so git must not block the async runtime
+                let library = self.clone();
+                tokio::task::spawn_blocking(move || library.clone_or_fetch())
+                    .await
+                    .map_err(|error| Error::Git(error.to_string()))?
+            })
+            .await
+    }
+
+    fn clone_or_fetch(&self) -> Result<(LocalLibrary, String), Error> {
+        // Every reference gets its own clone, so that workflows that override the reference of the
+        // library can be rendered at the same time
+        let key = format!(
+            "{}@{}",
+            self.config.url(),
+            self.config.reference().unwrap_or_default()
+        );
+        let path = self.cache.repository_path(&key);
+
+        if !path.join(".git").is_dir() {
+            if self.offline {
+                return Err(Error::NotCached(self.config.url().into(), self.to_string()));
+            }
+
+            git(
+                None,
+                [
+                    OsStr::new("clone"),
+                    OsStr::new("--quiet"),
+                    OsStr::new("--no-checkout"),
+                    OsStr::new("--"),
+                    OsStr::new(self.config.url()),
+                    path.as_os_str(),
+                ],
+            )?;
+        } else if !self.offline {
+            git(
+                Some(&path),
+                ["fetch", "--quiet", "--force", "--tags", "origin"],
+            )?;
+        }
+
+        let revision = self.resolve(&path)?;
+        git(
+            Some(&path),
+            ["checkout", "--quiet", "--force", "--detach", &revision],
+        )?;
+
+        Ok((LocalLibrary::at(path), revision))
+    }
+
+    /// Resolves the configured reference to a commit in the clone
+    ///
+    /// Branches only exist as remote-tracking branches in the clone, so they are looked up first.
+    /// Tags and commits are looked up by their name.
+    fn resolve(&self, path: &Path) -> Result<String, Error> {
+        let Some(reference) = self.config.reference() else {
+            return git(
+                Some(path),
+                ["rev-parse", "--verify", "origin/HEAD^{commit}"],
+            );
+        };
+
+        [
+            format!("origin/{reference}^{{commit}}"),
+            format!("{reference}^{{commit}}"),
+        ]
+        .iter()
+        .find_map(|candidate| git(Some(path), ["rev-parse", "--verify", "--quiet", candidate]).ok())
+        .ok_or_else(|| Error::UnknownReference(reference.into(), self.to_string()))
+    }
+
+    /// Reports missing fragments against the repository instead of its clone in the cache
+    fn in_repository(&self, error: Error) -> Error {
+        match error {
+            Error::NotFound(name, _) => Error::NotFound(name, self.to_string()),
+            error => error,
+        }
+    }
+}
+
+impl Display for GitLibrary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "repository {}", self.config.url())?;
+
+        if let Some(reference) = self.config.reference() {
+            write!(f, "@{reference}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<'a> FragmentLibrary<'a> for GitLibrary {
+    async fn workflow(&self, name: &'a str) -> Result<Fragment, Error> {
+        self.checkout()
+            .await?
+            .workflow(name)
+            .await
+            .map_err(|error| self.in_repository(error))
+    }
+
+    async fn job(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error> {
+        self.checkout()
+            .await?
+            .job(workflow, name)
+            .await
+            .map_err(|error| self.in_repository(error))
+    }
+
+    async fn workflows(&self) -> Result<Vec<String>, Error> {
+        FragmentLibrary::<'a>::workflows(self.checkout().await?).await
+    }
+
+    async fn jobs(&self, workflow: &'a str) -> Result<Vec<String>, Error> {
+        self.checkout()
+            .await?
+            .jobs(workflow)
+            .await
+            .map_err(|error| self.in_repository(error))
+    }
+
+    async fn step(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error> {
+        self.checkout()
+            .await?
+            .step(workflow, name)
+            .await
+            .map_err(|error| self.in_repository(error))
+    }
+
+    async fn commit(&self, _library: Option<&'a str>) -> Result<Option<String>, Error> {
+        let (_, commit) = self.checkout_with_commit().await?;
+        Ok(Some(commit.clone()))
+    }
+}
+
+/// Runs `git` and returns its trimmed output
+///
+/// Prompts for credentials are disabled, so that a repository that requires authentication fails
+/// instead of blocking FlowCrafter.
+fn git<I, S>(directory: Option<&Path>, args: I) -> Result<String, Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let mut command = Command::new("git");
+    if let Some(directory) = directory {
+        command.current_dir(directory);
+    }
+
+    let output = command
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|error| Error::Git(error.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Git(stderr.trim().to_string()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use tempfile::{tempdir, TempDir};
+
+    use super::*;
+
+    struct Remote {
+        directory: TempDir,
+    }
+
+    impl Remote {
+        /// Creates a bare repository with a `rust` workflow, and a tag `v1` before the last commit
+        fn new() -> Self {
+            let remote = Self {
+                directory: tempdir().unwrap(),
+            };
+
+            git(Some(remote.directory.path()), ["init", "--quiet", "work"]).unwrap();
+            remote.commit("rust/workflow.yml", "name: Rust\n");
+            remote.commit("rust/lint.yml", "lint:\n  runs-on: ubuntu-latest\n");
+            git(Some(&remote.work()), ["tag", "v1"]).unwrap();
+            remote.commit("rust/lint.yml", "lint:\n  runs-on: macos-latest\n");
+
+            git(
+                Some(remote.directory.path()),
+                ["clone", "--quiet", "--bare", "work", "remote.git"],
+            )
+            .unwrap();
+
+            remote
+        }
+
+        fn work(&self) -> PathBuf {
+            self.directory.path().join("work")
+        }
+
+        fn url(&self) -> String {
+            self.directory
+                .path()
+                .join("remote.git")
+                .display()
+                .to_string()
+        }
+
+        fn commit(&self, path: &str, content: &str) {
+            let file = self.work().join(path);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, content).unwrap();
+
+            git(Some(&self.work()), ["add", path]).unwrap();
+            git(
+                Some(&self.work()),
+                [
+                    "-c",
+                    "user.name=FlowCrafter",
+                    "-c",
+                    "user.email=flowcrafter@example.com",
+                    "-c",
+                    "commit.gpgsign=false",
+                    "commit",
+                    "--quiet",
+                    "--message",
+                    path,
+                ],
+            )
+            .unwrap();
+        }
+
+        fn push(&self) {
+            git(Some(&self.work()), ["push", "--quiet", &self.url(), "HEAD"]).unwrap();
+        }
+
+        fn library(&self, cache: &TempDir, reference: Option<&str>) -> GitLibrary {
+            let mut config = GitConfiguration::builder().url(self.url()).build();
+            if let Some(reference) = reference {
+                config.set_reference(reference);
+            }
+
+            GitLibrary::new(config, Cache::new(cache.path()))
+        }
+    }
+
+    #[tokio::test]
+    async fn workflow_from_default_branch() {
+        let remote = Remote::new();
+        let cache = tempdir().unwrap();
+
+        let workflow = remote.library(&cache, None).workflow("rust").await.unwrap();
+
+        assert_eq!("name: Rust\n", workflow.template().get());
+    }
+
+    #[tokio::test]
+    async fn job_from_tag() {
+        let remote = Remote::new();
+        let cache = tempdir().unwrap();
+
+        let job = remote
+            .library(&cache, Some("v1"))
+            .job("rust", "lint")
+            .await
+            .unwrap();
+
+        assert!(job.template().get().contains("ubuntu-latest"));
+    }
+
+    #[tokio::test]
+    async fn job_fetches_new_commits() {
+        let remote = Remote::new();
+        let cache = tempdir().unwrap();
+
+        remote
+            .library(&cache, None)
+            .job("rust", "lint")
+            .await
+            .unwrap();
+        remote.commit("rust/lint.yml", "lint:\n  runs-on: windows-latest\n");
+        remote.push();
+
+        let job = remote
+            .library(&cache, None)
+            .job("rust", "lint")
+            .await
+            .unwrap();
+
+        assert!(job.template().get().contains("windows-latest"));
+    }
+
+    #[tokio::test]
+    async fn job_not_found() {
+        let remote = Remote::new();
+        let cache = tempdir().unwrap();
+
+        let error = remote
+            .library(&cache, None)
+            .job("rust", "test")
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            format!("failed to find 'test.yml' in repository {}", remote.url()),
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn workflows() {
+        let remote = Remote::new();
+        let cache = tempdir().unwrap();
+
+        let workflows = remote.library(&cache, None).workflows().await.unwrap();
+
+        assert_eq!(vec!["rust"], workflows);
+    }
+
+    #[tokio::test]
+    async fn jobs() {
+        let remote = Remote::new();
+        let cache = tempdir().unwrap();
+
+        let jobs = remote.library(&cache, None).jobs("rust").await.unwrap();
+
+        assert_eq!(vec!["lint"], jobs);
+    }
+
+    #[tokio::test]
+    async fn commit_of_reference() {
+        let remote = Remote::new();
+        let cache = tempdir().unwrap();
+        let tag = git(Some(&remote.work()), ["rev-parse", "v1"]).unwrap();
+
+        let commit = remote
+            .library(&cache, Some("v1"))
+            .commit(None)
+            .await
+            .unwrap();
+
+        assert_eq!(Some(tag), commit);
+    }
+
+    #[tokio::test]
+    async fn unknown_reference() {
+        let remote = Remote::new();
+        let cache = tempdir().unwrap();
+
+        let error = remote
+            .library(&cache, Some("v2"))
+            .workflow("rust")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, Error::UnknownReference(reference, _) if reference == "v2"));
+    }
+
+    #[tokio::test]
+    async fn offline_uses_existing_clone() {
+        let remote = Remote::new();
+        let cache = tempdir().unwrap();
+
+        remote.library(&cache, None).workflow("rust").await.unwrap();
+        std::fs::remove_dir_all(remote.directory.path().join("remote.git")).unwrap();
+
+        let workflow = remote
+            .library(&cache, None)
+            .offline(true)
+            .workflow("rust")
+            .await
+            .unwrap();
+
+        assert_eq!("name: Rust\n", workflow.template().get());
+    }
+
+    #[tokio::test]
+    async fn offline_without_clone() {
+        let remote = Remote::new();
+        let cache = tempdir().unwrap();
+
+        let error = remote
+            .library(&cache, None)
+            .offline(true)
+            .workflow("rust")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, Error::NotCached(_, _)));
+    }
+
+    #[test]
+    fn trait_display() {
+        let library = GitLibrary::new(
+            GitConfiguration::builder()
+                .url("https://gitlab.com/jdno/workflows.git")
+                .reference("v1.0.0")
+                .build(),
+            Cache::new("/tmp/flowcrafter"),
+        );
+
+        assert_eq!(
+            "repository https://gitlab.com/jdno/workflows.git@v1.0.0",
+            library.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<GitLibrary>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<GitLibrary>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<GitLibrary>();
+    }
+}
@@ -0,0 +1,235 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use typed_builder::TypedBuilder;
+
+use crate::error::Error;
+
+/// Environment variables that are checked for a token when no authentication is configured
+const TOKEN_VARIABLES: [&str; 2] = ["GITHUB_TOKEN", "GH_TOKEN"];
+
+/// The source of the credentials that are used to authenticate with GitHub
+///
+/// The configuration only ever references where a secret can be found, so that it is never written
+/// into the configuration file.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Authentication {
+    /// Read a personal access token from an environment variable
+    Environment(String),
+    /// Read a personal access token from a file
+    File(PathBuf),
+    /// Authenticate as an installation of a GitHub App
+    App(AppAuthentication),
+}
+
+impl Authentication {
+    /// Returns a token from the first well-known environment variable that is set
+    pub fn discover() -> Option<String> {
+        discover_token(&TOKEN_VARIABLES)
+    }
+
+    /// Reads the personal access token from the configured source
+    pub fn token(&self) -> Result<String, Error> {
+        let token = match self {
+            Authentication::Environment(variable) => std::env::var(variable).map_err(|_| {
+                Error::Authentication(format!("environment variable '{variable}' is not set"))
+            })?,
+            Authentication::File(path) => std::fs::read_to_string(path).map_err(|error| {
+                Error::Authentication(format!(
+                    "failed to read token from '{}': {error}",
+                    path.display()
+                ))
+            })?,
+            Authentication::App(_) => {
+                return Err(Error::Authentication(
+                    "GitHub Apps authenticate with a private key instead of a token".into(),
+                ))
+            }
+        };
+
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(Error::Authentication(format!(
+                "the token in {self} is empty"
+            )));
+        }
+
+        Ok(token.into())
+    }
+}
+
+impl Display for Authentication {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Authentication::Environment(variable) => {
+                write!(f, "environment variable {variable}")
+            }
+            Authentication::File(path) => write!(f, "file {}", path.display()),
+            Authentication::App(app) => write!(f, "GitHub App {}", app.id),
+        }
+    }
+}
+
+/// The credentials of a GitHub App that has been installed for the template repository
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, TypedBuilder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct AppAuthentication {
+    id: u64,
+
+    #[builder(setter(into))]
+    key_file: PathBuf,
+
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[builder(default, setter(strip_option))]
+    installation: Option<u64>,
+}
+
+impl AppAuthentication {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn key_file(&self) -> &Path {
+        &self.key_file
+    }
+
+    /// Returns the id of the installation, which is looked up for the repository if not set
+    pub fn installation(&self) -> Option<u64> {
+        self.installation
+    }
+}
+
+fn discover_token(variables: &[&str]) -> Option<String> {
+    variables
+        .iter()
+        .filter_map(|variable| std::env::var(variable).ok())
+        .map(|token| token.trim().to_string())
+        .find(|token| !token.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn discover_token_skips_missing_variables() {
+        std::env::set_var("FLOWCRAFTER_TEST_DISCOVER_TOKEN", "secret");
+
+        let token = discover_token(&[
+            "FLOWCRAFTER_TEST_MISSING_TOKEN",
+            "FLOWCRAFTER_TEST_DISCOVER_TOKEN",
+        ]);
+
+        assert_eq!(Some("secret".into()), token);
+    }
+
+    #[test]
+    fn token_from_environment() {
+        std::env::set_var("FLOWCRAFTER_TEST_ENVIRONMENT_TOKEN", "secret\n");
+
+        let authentication =
+            Authentication::Environment("FLOWCRAFTER_TEST_ENVIRONMENT_TOKEN".into());
+
+        assert_eq!("secret", authentication.token().unwrap());
+    }
+
+    #[test]
+    fn token_from_missing_environment_variable() {
+        let authentication = Authentication::Environment("FLOWCRAFTER_TEST_UNSET_TOKEN".into());
+
+        let error = authentication.token().unwrap_err();
+
+        assert!(matches!(error, Error::Authentication(_)));
+    }
+
+    #[test]
+    fn token_from_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "secret\n").unwrap();
+
+        let authentication = Authentication::File(file.path().into());
+
+        assert_eq!("secret", authentication.token().unwrap());
+    }
+
+    #[test]
+    fn token_from_empty_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+
+        let authentication = Authentication::File(file.path().into());
+
+        assert!(authentication.token().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_environment() {
+        let authentication: Authentication = serde_yaml::with::singleton_map::deserialize(
+            serde_yaml::Deserializer::from_str("environment: MY_TOKEN"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            Authentication::Environment("MY_TOKEN".into()),
+            authentication
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_app() {
+        let yaml = indoc!(
+            r#"
+            ---
+            app:
+              id: 42
+              key-file: app.pem
+            "#
+        );
+
+        let authentication: Authentication =
+            serde_yaml::with::singleton_map::deserialize(serde_yaml::Deserializer::from_str(yaml))
+                .unwrap();
+
+        assert_eq!(
+            Authentication::App(
+                AppAuthentication::builder()
+                    .id(42)
+                    .key_file("app.pem")
+                    .build()
+            ),
+            authentication
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        let authentication = Authentication::Environment("MY_TOKEN".into());
+
+        assert_eq!("environment variable MY_TOKEN", authentication.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Authentication>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Authentication>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Authentication>();
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt::Display;
 use typed_builder::TypedBuilder;
 use url::Url;
 
+use crate::github::authentication::Authentication;
 use crate::github::owner::Owner;
 use crate::github::repository::Repository;
 
@@ -19,6 +20,18 @@ pub struct GitHubConfiguration {
 
     #[builder(setter(into))]
     repository: Repository,
+
+    #[cfg_attr(feature = "serde", serde(rename = "ref"))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[builder(default, setter(into, strip_option))]
+    reference: Option<String>,
+
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[cfg_attr(feature = "serde", serde(with = "serde_yaml::with::singleton_map"))]
+    #[builder(default, setter(strip_option))]
+    authentication: Option<Authentication>,
 }
 
 impl GitHubConfiguration {
@@ -33,6 +46,24 @@ impl GitHubConfiguration {
     pub fn repository(&self) -> &Repository {
         &self.repository
     }
+
+    /// Returns the branch, tag, or commit that templates are read from
+    ///
+    /// Templates are read from the default branch of the repository if no reference is set.
+    pub fn reference(&self) -> Option<&str> {
+        self.reference.as_deref()
+    }
+
+    pub fn set_reference(&mut self, reference: impl Into<String>) {
+        self.reference = Some(reference.into());
+    }
+
+    /// Returns the configured authentication, if any
+    ///
+    /// Without explicit configuration, a token is discovered from the environment.
+    pub fn authentication(&self) -> Option<&Authentication> {
+        self.authentication.as_ref()
+    }
 }
 
 impl Display for GitHubConfiguration {
@@ -90,6 +121,44 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_reference() {
+        let yaml = indoc!(
+            r#"
+            ---
+            owner: jdno
+            repository: flowcrafter
+            ref: v1.0.0
+            "#
+        );
+
+        let configuration = serde_yaml::from_str::<GitHubConfiguration>(yaml).unwrap();
+
+        assert_eq!(Some("v1.0.0"), configuration.reference());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_authentication() {
+        let yaml = indoc!(
+            r#"
+            ---
+            owner: jdno
+            repository: flowcrafter
+            authentication:
+              file: /run/secrets/github
+            "#
+        );
+
+        let configuration = serde_yaml::from_str::<GitHubConfiguration>(yaml).unwrap();
+
+        assert_eq!(
+            Some(&Authentication::File("/run/secrets/github".into())),
+            configuration.authentication()
+        );
+    }
+
     #[test]
     fn trait_display() {
         let configuration = GitHubConfiguration::builder()
@@ -144,6 +213,47 @@ mod tests {
         assert_eq!(yaml, serde_yaml::to_string(&configuration).unwrap());
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_serialize_with_reference() {
+        let configuration = GitHubConfiguration::builder()
+            .owner("jdno")
+            .repository("flowcrafter")
+            .reference("v1.0.0")
+            .build();
+
+        let yaml = indoc!(
+            r#"
+            owner: jdno
+            repository: flowcrafter
+            ref: v1.0.0
+            "#
+        );
+
+        assert_eq!(yaml, serde_yaml::to_string(&configuration).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_serialize_with_authentication() {
+        let configuration = GitHubConfiguration::builder()
+            .owner("jdno")
+            .repository("flowcrafter")
+            .authentication(Authentication::Environment("FLOWCRAFTER_TOKEN".into()))
+            .build();
+
+        let yaml = indoc!(
+            r#"
+            owner: jdno
+            repository: flowcrafter
+            authentication:
+              environment: FLOWCRAFTER_TOKEN
+            "#
+        );
+
+        assert_eq!(yaml, serde_yaml::to_string(&configuration).unwrap());
+    }
+
     #[test]
     fn trait_sync() {
         fn assert_sync<T: Sync>() {}
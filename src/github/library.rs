@@ -1,55 +1,406 @@
-use std::fmt::Display;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use base64::engine::general_purpose::PAD;
 use base64::engine::GeneralPurpose;
 use base64::{alphabet, Engine};
-use octocrab::models::repos::Content;
-use octocrab::Octocrab;
+use http::header::{ACCEPT, ETAG, IF_NONE_MATCH};
+use http::{HeaderMap, HeaderValue, StatusCode};
+use jsonwebtoken::EncodingKey;
+use octocrab::models::repos::{Content, ContentItems};
+use octocrab::models::{AppId, InstallationId};
+use octocrab::{FromResponse, Octocrab};
+use tokio::sync::{Mutex, OnceCell, Semaphore, SemaphorePermit};
+use url::form_urlencoded::byte_serialize;
 
+use crate::cache::{Cache, CacheEntry};
 use crate::error::Error;
-use crate::fragment::{Fragment, FragmentLibrary};
-use crate::github::GitHubConfiguration;
-use crate::template::Template;
+use crate::fragment::{job_name, sidecar_path, step_paths, Fragment, FragmentLibrary};
+use crate::github::{AppAuthentication, Authentication, GitHubConfiguration};
 
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+/// The maximum number of requests that a library sends to GitHub at the same time
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// The start of the message that GitHub returns when a reference does not exist
+const UNKNOWN_REFERENCE: &str = "No commit found for the ref";
+
+/// The media type that makes GitHub return only the SHA of a commit
+const COMMIT_SHA_MEDIA_TYPE: &str = "application/vnd.github.sha";
+
+/// The names of the files in a directory, which is listed when it is first needed
+type Listing = Arc<OnceCell<HashSet<String>>>;
+
+/// A library of fragments in a repository on GitHub
+///
+/// The API client is created when the first fragment is downloaded, and then shared by all
+/// requests of the library.
+///
+/// When a cache is configured, downloaded fragments are stored in it together with their ETag.
+/// Subsequent downloads are conditional requests that only transfer the fragment if it has changed.
+///
+/// Fragments can be downloaded concurrently, but the library never sends more than
+/// [`MAX_CONCURRENT_REQUESTS`] requests to GitHub at the same time to stay clear of its rate limits.
+///
+/// All fragments are read from the same commit. The configured reference, or the default branch,
+/// is resolved to a commit when the first fragment is downloaded. Offline, the configured reference
+/// is used as is.
+///
+/// Sidecar files are only requested if they exist. The library lists the files in a directory
+/// the first time it downloads a fragment from it, and reuses the listing for all other fragments
+/// in the directory.
+#[derive(Clone)]
 pub struct GitHubLibrary {
     config: GitHubConfiguration,
+    client: OnceCell<Octocrab>,
+    commit: OnceCell<String>,
+    cache: Option<Cache>,
+    offline: bool,
+    listings: Arc<Mutex<HashMap<String, Listing>>>,
+    requests: Arc<Semaphore>,
 }
 
 impl GitHubLibrary {
     pub fn new(config: GitHubConfiguration) -> Self {
-        Self { config }
+        Self {
+            config,
+            client: OnceCell::new(),
+            commit: OnceCell::new(),
+            cache: None,
+            offline: false,
+            listings: Arc::default(),
+            requests: Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS)),
+        }
+    }
+
+    /// Stores downloaded fragments in the cache
+    pub fn cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Reads fragments only from the cache instead of downloading them
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
     }
 
     async fn download(&self, name: &str, path: &str) -> Result<Fragment, Error> {
-        let file = self.fetch_from_github(path).await?;
-        let content = self.decode_content(file)?;
+        let (file, sidecar) = tokio::try_join!(self.fetch_file(path), self.fetch_sidecar(path))?;
 
-        let fragment = Fragment::builder()
-            .name(name)
-            .template(Template::new(content))
-            .build();
+        let mut fragment = Fragment::parse_with_sidecar(
+            name,
+            file.content(),
+            sidecar.as_ref().map(CacheEntry::content),
+        )?;
+        fragment.set_revision(match &sidecar {
+            Some(sidecar) => format!("{}+{}", file.revision(), sidecar.revision()),
+            None => file.revision().to_string(),
+        });
 
         Ok(fragment)
     }
 
-    async fn fetch_from_github(&self, path: &str) -> Result<Content, Error> {
-        let items = Octocrab::builder()
-            .base_uri(self.config.instance().to_string())?
-            .build()?
-            .repos(self.config.owner().get(), self.config.repository().get())
-            .get_content()
-            .path(path)
-            .send()
+    /// Returns the optional sidecar file with the metadata of a fragment
+    ///
+    /// Offline, the sidecar is read from the cache if it has been downloaded before.
+    async fn fetch_sidecar(&self, path: &str) -> Result<Option<CacheEntry>, Error> {
+        let path = sidecar_path(path);
+
+        if self.offline {
+            return match self.fetch_file(&path).await {
+                Ok(sidecar) => Ok(Some(sidecar)),
+                Err(Error::NotCached(_, _)) => Ok(None),
+                Err(error) => Err(error),
+            };
+        }
+
+        if !self.contains_file(&path).await? {
+            return Ok(None);
+        }
+
+        self.fetch_file(&path).await.map(Some)
+    }
+
+    /// Checks if a file exists by looking it up in the listing of its directory
+    ///
+    /// Each directory is only listed once. A directory that does not exist contains no files.
+    async fn contains_file(&self, path: &str) -> Result<bool, Error> {
+        let (directory, name) = path.rsplit_once('/').unwrap_or(("", path));
+
+        // Only hold the lock to look up the listing, so that other directories can be listed at the
+        // same time while this one is downloaded
+        let listing = self
+            .listings
+            .lock()
             .await
-            .map_err(|_| Error::NotFound(path.into(), self.to_string()))?;
+            .entry(directory.into())
+            .or_default()
+            .clone();
+
+        let files = listing
+            .get_or_try_init(|| async {
+                match self.list_from_github(directory).await {
+                    Ok(contents) => Ok(contents
+                        .into_iter()
+                        .filter(|content| content.r#type == "file")
+                        .map(|content| content.name)
+                        .collect()),
+                    Err(Error::NotFound(_, _)) => Ok(HashSet::new()),
+                    Err(error) => Err(error),
+                }
+            })
+            .await?;
+
+        Ok(files.contains(name))
+    }
+
+    /// Returns a file from the cache if it is up to date, and downloads it from GitHub otherwise
+    async fn fetch_file(&self, path: &str) -> Result<CacheEntry, Error> {
+        let key = self.cache_key(self.reference().await?, path);
+        let cached = self.cache.as_ref().and_then(|cache| cache.get(&key));
+
+        if self.offline {
+            return cached.ok_or_else(|| Error::NotCached(path.into(), self.to_string()));
+        }
+
+        let etag = cached.as_ref().and_then(CacheEntry::etag);
+        let Some((file, etag)) = self.fetch_from_github(path, etag).await? else {
+            return cached.ok_or_else(|| Error::NotFound(path.into(), self.to_string()));
+        };
+
+        let revision = file.sha.clone();
+        let entry = CacheEntry::new(revision, etag, self.decode_content(file)?);
+
+        if let Some(cache) = &self.cache {
+            // Offline, the configured reference cannot be resolved to a commit, so the file is
+            // cached under both
+            let configured_key = self.cache_key(self.config.reference(), path);
+
+            // The cache only speeds up future downloads, so failing to write to it is not fatal
+            let _ = cache.insert(&key, &entry);
+            if configured_key != key {
+                let _ = cache.insert(&configured_key, &entry);
+            }
+        }
+
+        Ok(entry)
+    }
+
+    fn cache_key(&self, reference: Option<&str>, path: &str) -> String {
+        format!(
+            "{}/{}/{}/{}/{path}",
+            self.config.instance(),
+            self.config.owner(),
+            self.config.repository(),
+            reference.unwrap_or_default()
+        )
+    }
 
-        items
+    /// Returns the reference that fragments are read from
+    ///
+    /// Online, this is the commit that the configured reference resolves to. Offline, the
+    /// configured reference is used as is, which is the locked commit if the workflow is locked.
+    async fn reference(&self) -> Result<Option<&str>, Error> {
+        if self.offline {
+            return Ok(self.config.reference());
+        }
+
+        let commit = self.commit.get_or_try_init(|| self.fetch_commit()).await?;
+
+        Ok(Some(commit))
+    }
+
+    /// Resolves the configured reference, or the default branch, to the SHA of a commit
+    async fn fetch_commit(&self) -> Result<String, Error> {
+        let client = self.client().await?;
+        let _permit = self.permit().await;
+
+        let reference = self.config.reference().unwrap_or("HEAD");
+        let route = format!(
+            "/repos/{}/{}/commits/{}",
+            self.config.owner(),
+            self.config.repository(),
+            byte_serialize(reference.as_bytes()).collect::<String>()
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static(COMMIT_SHA_MEDIA_TYPE));
+
+        let response = client._get_with_headers(route, Some(headers)).await?;
+        let response =
+            octocrab::map_github_error(response)
+                .await
+                .map_err(|error| match &error {
+                    octocrab::Error::GitHub { source, .. }
+                        if source.status_code == StatusCode::UNPROCESSABLE_ENTITY =>
+                    {
+                        Error::UnknownReference(reference.into(), self.to_string())
+                    }
+                    _ => self.map_error(reference, error),
+                })?;
+
+        Ok(client.body_to_string(response).await?.trim().to_string())
+    }
+
+    async fn client(&self) -> Result<&Octocrab, Error> {
+        self.client.get_or_try_init(|| self.build_client()).await
+    }
+
+    async fn build_client(&self) -> Result<Octocrab, Error> {
+        let builder = Octocrab::builder().base_uri(self.config.instance().to_string())?;
+
+        let authentication = match self.config.authentication() {
+            Some(Authentication::App(app)) => return self.app_client(app).await,
+            Some(authentication) => Some(authentication.token()?),
+            None => Authentication::discover(),
+        };
+
+        let client = match authentication {
+            Some(token) => builder.personal_token(token).build()?,
+            None => builder.build()?,
+        };
+
+        Ok(client)
+    }
+
+    async fn app_client(&self, app: &AppAuthentication) -> Result<Octocrab, Error> {
+        let key = std::fs::read(app.key_file()).map_err(|error| {
+            Error::Authentication(format!(
+                "failed to read private key from '{}': {error}",
+                app.key_file().display()
+            ))
+        })?;
+        let key = EncodingKey::from_rsa_pem(&key).map_err(|error| {
+            Error::Authentication(format!("failed to parse private key: {error}"))
+        })?;
+
+        let client = Octocrab::builder()
+            .base_uri(self.config.instance().to_string())?
+            .app(AppId(app.id()), key)
+            .build()?;
+
+        let installation = match app.installation() {
+            Some(installation) => InstallationId(installation),
+            None => {
+                client
+                    .apps()
+                    .get_repository_installation(
+                        self.config.owner().get(),
+                        self.config.repository().get(),
+                    )
+                    .await?
+                    .id
+            }
+        };
+
+        Ok(client.installation(installation))
+    }
+
+    /// Downloads a file and its ETag, or returns `None` if the file still matches the given ETag
+    async fn fetch_from_github(
+        &self,
+        path: &str,
+        etag: Option<&str>,
+    ) -> Result<Option<(Content, Option<String>)>, Error> {
+        let client = self.client().await?;
+        // The commit is resolved before waiting for a permit, since resolving it needs one as well
+        let reference = self.reference().await?;
+        let _permit = self.permit().await;
+
+        let mut route = format!(
+            "/repos/{}/{}/contents/{path}",
+            self.config.owner(),
+            self.config.repository()
+        );
+        if let Some(reference) = reference {
+            let reference: String = byte_serialize(reference.as_bytes()).collect();
+            route.push_str(&format!("?ref={reference}"));
+        }
+
+        let mut headers = HeaderMap::new();
+        if let Some(etag) = etag.and_then(|etag| HeaderValue::from_str(etag).ok()) {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+
+        let response = client._get_with_headers(route, Some(headers)).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(String::from);
+
+        let not_found = || Error::NotFound(path.into(), self.to_string());
+
+        let response = octocrab::map_github_error(response)
+            .await
+            .map_err(|error| self.map_error(path, error))?;
+        let file = ContentItems::from_response(response)
+            .await?
             .items
             .into_iter()
             .next()
-            .ok_or(Error::NotFound(path.into(), self.to_string()))
+            .ok_or_else(not_found)?;
+
+        Ok(Some((file, etag)))
+    }
+
+    /// Returns the contents of a path, which is a list of entries if the path is a directory
+    async fn list_from_github(&self, path: &str) -> Result<Vec<Content>, Error> {
+        if self.offline {
+            return Err(Error::NotCached(path.into(), self.to_string()));
+        }
+
+        let client = self.client().await?;
+        let reference = self.reference().await?;
+        let _permit = self.permit().await;
+        let repository = client.repos(self.config.owner().get(), self.config.repository().get());
+
+        let mut request = repository.get_content().path(path);
+        if let Some(reference) = reference {
+            request = request.r#ref(reference);
+        }
+
+        let items = request
+            .send()
+            .await
+            .map_err(|error| self.map_error(path, error))?;
+
+        Ok(items.items)
+    }
+
+    /// Waits until fewer than [`MAX_CONCURRENT_REQUESTS`] requests are in flight
+    async fn permit(&self) -> SemaphorePermit<'_> {
+        self.requests
+            .acquire()
+            .await
+            .expect("semaphore is never closed")
+    }
+
+    /// Converts an error from the GitHub API, which is only a missing path if GitHub returned 404
+    ///
+    /// GitHub also returns 404 when the configured reference does not exist. This is reported as
+    /// its own error, so that layered libraries do not mistake it for a missing fragment.
+    fn map_error(&self, path: &str, error: octocrab::Error) -> Error {
+        match &error {
+            octocrab::Error::GitHub { source, .. } => match source.status_code {
+                StatusCode::NOT_FOUND if source.message.starts_with(UNKNOWN_REFERENCE) => {
+                    Error::UnknownReference(
+                        self.config.reference().unwrap_or_default().into(),
+                        self.to_string(),
+                    )
+                }
+                StatusCode::NOT_FOUND => Error::NotFound(path.into(), self.to_string()),
+                StatusCode::UNAUTHORIZED => Error::Authentication(source.message.clone()),
+                _ => error.into(),
+            },
+            _ => error.into(),
+        }
     }
 
     fn decode_content(&self, content: Content) -> Result<String, Error> {
@@ -81,19 +432,97 @@ impl<'a> FragmentLibrary<'a> for GitHubLibrary {
         let path = format!("{workflow}/{name}.yml");
         self.download(name, &path).await
     }
+
+    /// Returns the directories at the root of the repository, except for hidden directories and
+    /// the shared `steps` directory
+    ///
+    /// Checking each directory for a `workflow.yml` would require a request per directory, so
+    /// the directories in the repository are assumed to be workflows.
+    async fn workflows(&self) -> Result<Vec<String>, Error> {
+        let mut workflows: Vec<String> = self
+            .list_from_github("")
+            .await?
+            .into_iter()
+            .filter(|content| content.r#type == "dir" && !content.name.starts_with('.'))
+            .filter(|content| content.name != "steps")
+            .map(|content| content.name)
+            .collect();
+
+        workflows.sort();
+        Ok(workflows)
+    }
+
+    async fn jobs(&self, workflow: &'a str) -> Result<Vec<String>, Error> {
+        let mut jobs: Vec<String> = self
+            .list_from_github(workflow)
+            .await?
+            .iter()
+            .filter(|content| content.r#type == "file")
+            .filter_map(|content| job_name(&content.name).map(String::from))
+            .collect();
+
+        jobs.sort();
+        Ok(jobs)
+    }
+
+    async fn step(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error> {
+        let [workflow_step, shared_step] = step_paths(workflow, name);
+
+        match self.download(name, &workflow_step).await {
+            Err(Error::NotFound(_, _) | Error::NotCached(_, _)) => {
+                self.download(name, &shared_step).await
+            }
+            result => result,
+        }
+    }
+
+    /// Returns the commit that fragments are read from, which is only known offline if the
+    /// configured reference is a commit
+    async fn commit(&self, _library: Option<&'a str>) -> Result<Option<String>, Error> {
+        if self.offline {
+            return Ok(self
+                .config
+                .reference()
+                .filter(|reference| is_commit(reference))
+                .map(String::from));
+        }
+
+        Ok(self.reference().await?.map(String::from))
+    }
+}
+
+impl Debug for GitHubLibrary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitHubLibrary")
+            .field("config", &self.config)
+            .field("cache", &self.cache)
+            .field("offline", &self.offline)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Display for GitHubLibrary {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
             "repository {}/{}",
             self.config.owner(),
             self.config.repository()
-        )
+        )?;
+
+        if let Some(reference) = self.config.reference() {
+            write!(f, "@{reference}")?;
+        }
+
+        Ok(())
     }
 }
 
+/// Checks if a reference is the full SHA of a commit
+fn is_commit(reference: &str) -> bool {
+    reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit())
+}
+
 fn base64_decode(base64_encoded_string: &str) -> Result<Vec<u8>, base64::DecodeError> {
     let sanitized_input = base64_encoded_string.replace('\n', "");
 
@@ -105,9 +534,12 @@ mod tests {
     use indoc::indoc;
 
     use crate::github::{Owner, Repository};
+    use crate::template::Template;
 
     use super::*;
 
+    const COMMIT: &str = "7638417db6d59f3c431d3e1f261cc637155684cd";
+
     const WORKFLOW: &str = indoc! {r#"
         ---
         name: Test
@@ -168,6 +600,64 @@ mod tests {
         }
     "#};
 
+    fn directory_entry(path: &str, r#type: &str) -> String {
+        let name = path.rsplit('/').next().unwrap();
+
+        format!(
+            r#"{{
+                "type": "{type}",
+                "size": 0,
+                "name": "{name}",
+                "path": "{path}",
+                "sha": "3d21ec53a331a6f037a91c368710b99387d012c1",
+                "url": "https://api.github.com/repos/owner/name/contents/{path}",
+                "git_url": null,
+                "html_url": null,
+                "download_url": null,
+                "_links": {{
+                    "git": null,
+                    "self": "https://api.github.com/repos/owner/name/contents/{path}",
+                    "html": null
+                }}
+            }}"#
+        )
+    }
+
+    fn mock_directory(
+        server: &mut mockito::ServerGuard,
+        directory: &str,
+        files: &[&str],
+    ) -> mockito::Mock {
+        let entries: Vec<String> = files
+            .iter()
+            .map(|file| directory_entry(&format!("{directory}/{file}"), "file"))
+            .collect();
+
+        server
+            .mock(
+                "GET",
+                format!("/repos/owner/name/contents/{directory}").as_str(),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(format!("[{}]", entries.join(", ")))
+            .create()
+    }
+
+    /// Resolves every reference to [`COMMIT`]
+    fn mock_commit(server: &mut mockito::ServerGuard) -> mockito::Mock {
+        server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex("^/repos/owner/name/commits/".into()),
+            )
+            .match_header("accept", COMMIT_SHA_MEDIA_TYPE)
+            .with_status(200)
+            .with_body(COMMIT)
+            .create()
+    }
+
     fn build_config(server_url: &str) -> GitHubConfiguration {
         GitHubConfiguration::builder()
             .instance(server_url.parse().unwrap())
@@ -179,6 +669,8 @@ mod tests {
     #[tokio::test]
     async fn workflow() {
         let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        mock_directory(&mut server, "test", &["workflow.yml"]);
         let mock = server
             .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
             .match_query(mockito::Matcher::Any)
@@ -195,11 +687,17 @@ mod tests {
         mock.assert();
         assert_eq!("test", workflow.name());
         assert_eq!(&Template::new(WORKFLOW), workflow.template());
+        assert_eq!(
+            Some("3d21ec53a331a6f037a91c368710b99387d012c1"),
+            workflow.revision()
+        );
     }
 
     #[tokio::test]
     async fn job() {
         let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        mock_directory(&mut server, "test", &["job.yml"]);
         let mock = server
             .mock("GET", "/repos/owner/name/contents/test/job.yml")
             .match_query(mockito::Matcher::Any)
@@ -218,6 +716,399 @@ mod tests {
         assert_eq!(&Template::new(JOB), job.template());
     }
 
+    #[tokio::test]
+    async fn step_from_shared_steps() {
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        server
+            .mock("GET", "/repos/owner/name/contents/test/steps")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(r#"{"message": "Not Found"}"#)
+            .create();
+        mock_directory(&mut server, "steps", &["setup.yml"]);
+        let workflow_step = server
+            .mock("GET", "/repos/owner/name/contents/test/steps/setup.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(r#"{"message": "Not Found"}"#)
+            .create();
+        let shared_step = server
+            .mock("GET", "/repos/owner/name/contents/steps/setup.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(JOB_RESPONSE)
+            .create();
+
+        let config = build_config(&server.url());
+        let library = GitHubLibrary::new(config);
+
+        let step = library.step("test", "setup").await.unwrap();
+
+        workflow_step.assert();
+        shared_step.assert();
+        assert_eq!("setup", step.name());
+    }
+
+    #[tokio::test]
+    async fn job_with_sidecar() {
+        let content = GeneralPurpose::new(&alphabet::STANDARD, PAD).encode("author: jdno\n");
+        let sidecar = format!(
+            r#"{{
+                "type": "file",
+                "encoding": "base64",
+                "size": 13,
+                "name": "job.meta.yml",
+                "path": "test/job.meta.yml",
+                "content": "{content}",
+                "sha": "9f0a8f5ad6b1cba7e1e5e7e2d0c0f7b3a8b2c1d4",
+                "url": "https://api.github.com/repos/owner/name/contents/test/job.meta.yml",
+                "git_url": null,
+                "html_url": null,
+                "download_url": null,
+                "_links": {{
+                    "git": null,
+                    "self": "https://api.github.com/repos/owner/name/contents/test/job.meta.yml",
+                    "html": null
+                }}
+            }}"#
+        );
+
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        mock_directory(&mut server, "test", &["job.yml", "job.meta.yml"]);
+        server
+            .mock("GET", "/repos/owner/name/contents/test/job.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(JOB_RESPONSE)
+            .create();
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/test/job.meta.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(sidecar)
+            .create();
+
+        let library = GitHubLibrary::new(build_config(&server.url()));
+
+        let job = library.job("test", "job").await.unwrap();
+
+        mock.assert();
+        assert_eq!(&Template::new(JOB), job.template());
+        assert_eq!(Some("jdno"), job.metadata().author());
+        assert_eq!(
+            Some(
+                "3d21ec53a331a6f037a91c368710b99387d012c1+9f0a8f5ad6b1cba7e1e5e7e2d0c0f7b3a8b2c1d4"
+            ),
+            job.revision()
+        );
+    }
+
+    #[tokio::test]
+    async fn job_without_sidecar() {
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        mock_directory(&mut server, "test", &["job.yml"]);
+        server
+            .mock("GET", "/repos/owner/name/contents/test/job.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(JOB_RESPONSE)
+            .create();
+        let sidecar = server
+            .mock("GET", "/repos/owner/name/contents/test/job.meta.yml")
+            .match_query(mockito::Matcher::Any)
+            .expect(0)
+            .create();
+
+        let library = GitHubLibrary::new(build_config(&server.url()));
+
+        let job = library.job("test", "job").await.unwrap();
+
+        sidecar.assert();
+        assert_eq!(None, job.metadata().author());
+    }
+
+    #[tokio::test]
+    async fn directory_is_listed_once() {
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        let listing = mock_directory(&mut server, "test", &["job.yml"]);
+        server
+            .mock("GET", "/repos/owner/name/contents/test/job.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(JOB_RESPONSE)
+            .expect(2)
+            .create();
+
+        let library = GitHubLibrary::new(build_config(&server.url()));
+
+        library.job("test", "job").await.unwrap();
+        library.job("test", "job").await.unwrap();
+
+        listing.assert();
+    }
+
+    #[tokio::test]
+    async fn directory_is_listed_once_when_downloading_concurrently() {
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        let listing = mock_directory(&mut server, "test", &["job.yml"]);
+        server
+            .mock("GET", "/repos/owner/name/contents/test/job.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(JOB_RESPONSE)
+            .expect(2)
+            .create();
+
+        let library = GitHubLibrary::new(build_config(&server.url()));
+
+        let (first, second) = tokio::join!(library.job("test", "job"), library.job("test", "job"));
+        first.unwrap();
+        second.unwrap();
+
+        listing.assert();
+    }
+
+    #[tokio::test]
+    async fn workflows() {
+        let body = format!(
+            "[{}, {}, {}, {}]",
+            directory_entry("rust", "dir"),
+            directory_entry(".github", "dir"),
+            directory_entry("README.md", "file"),
+            directory_entry("markdown", "dir"),
+        );
+
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(body)
+            .create();
+
+        let library = GitHubLibrary::new(build_config(&server.url()));
+
+        let workflows = library.workflows().await.unwrap();
+
+        mock.assert();
+        assert_eq!(vec!["markdown", "rust"], workflows);
+    }
+
+    #[tokio::test]
+    async fn jobs() {
+        let body = format!(
+            "[{}, {}, {}]",
+            directory_entry("rust/workflow.yml", "file"),
+            directory_entry("rust/test.yml", "file"),
+            directory_entry("rust/lint.yml", "file"),
+        );
+
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/rust")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(body)
+            .create();
+
+        let library = GitHubLibrary::new(build_config(&server.url()));
+
+        let jobs = library.jobs("rust").await.unwrap();
+
+        mock.assert();
+        assert_eq!(vec!["lint", "test"], jobs);
+    }
+
+    #[tokio::test]
+    async fn workflow_with_reference() {
+        let mut server = mockito::Server::new_async().await;
+        let commit = server
+            .mock("GET", "/repos/owner/name/commits/v1.0.0")
+            .with_status(200)
+            .with_body(COMMIT)
+            .create();
+        mock_directory(&mut server, "test", &["workflow.yml"]);
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
+            .match_query(mockito::Matcher::UrlEncoded("ref".into(), COMMIT.into()))
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(WORKFLOW_RESPONSE)
+            .create();
+
+        let mut config = build_config(&server.url());
+        config.set_reference("v1.0.0");
+        let library = GitHubLibrary::new(config);
+
+        library.workflow("test").await.unwrap();
+
+        commit.assert();
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn workflow_with_unknown_reference() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/name/commits/v2")
+            .with_status(422)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(indoc!(
+                r#"
+                {
+                    "message": "No commit found for SHA: v2",
+                    "documentation_url": "https://docs.github.com/rest/commits/commits#get-a-commit"
+                }
+                "#
+            ))
+            .create();
+
+        let mut config = build_config(&server.url());
+        config.set_reference("v2");
+        let library = GitHubLibrary::new(config);
+
+        let error = library.workflow("test").await.unwrap_err();
+
+        assert!(matches!(error, Error::UnknownReference(reference, _) if reference == "v2"));
+    }
+
+    #[tokio::test]
+    async fn commit_is_resolved_once() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = mock_commit(&mut server);
+        mock_directory(&mut server, "test", &["workflow.yml"]);
+        server
+            .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(WORKFLOW_RESPONSE)
+            .create();
+
+        let library = GitHubLibrary::new(build_config(&server.url()));
+        library.workflow("test").await.unwrap();
+
+        let commit = library.commit(None).await.unwrap();
+
+        mock.assert();
+        assert_eq!(Some(COMMIT.into()), commit);
+    }
+
+    #[tokio::test]
+    async fn commit_offline_is_configured_commit() {
+        let mut config = build_config("http://localhost");
+        config.set_reference(COMMIT);
+        let library = GitHubLibrary::new(config).offline(true);
+
+        assert_eq!(Some(COMMIT.into()), library.commit(None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn commit_offline_is_unknown_for_branches() {
+        let mut config = build_config("http://localhost");
+        config.set_reference("main");
+        let library = GitHubLibrary::new(config).offline(true);
+
+        assert_eq!(None, library.commit(None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn workflow_with_token_from_environment() {
+        std::env::set_var("FLOWCRAFTER_TEST_LIBRARY_TOKEN", "secret");
+
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        mock_directory(&mut server, "test", &["workflow.yml"]);
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
+            .match_query(mockito::Matcher::Any)
+            .match_header("authorization", "Bearer secret")
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(WORKFLOW_RESPONSE)
+            .create();
+
+        let config = GitHubConfiguration::builder()
+            .instance(server.url().parse().unwrap())
+            .owner("owner")
+            .repository("name")
+            .authentication(Authentication::Environment(
+                "FLOWCRAFTER_TEST_LIBRARY_TOKEN".into(),
+            ))
+            .build();
+        let library = GitHubLibrary::new(config);
+
+        library.workflow("test").await.unwrap();
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn workflow_with_token_from_file() {
+        let token_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(token_file.path(), "secret\n").unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        mock_directory(&mut server, "test", &["workflow.yml"]);
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
+            .match_query(mockito::Matcher::Any)
+            .match_header("authorization", "Bearer secret")
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(WORKFLOW_RESPONSE)
+            .create();
+
+        let config = GitHubConfiguration::builder()
+            .instance(server.url().parse().unwrap())
+            .owner("owner")
+            .repository("name")
+            .authentication(Authentication::File(token_file.path().into()))
+            .build();
+        let library = GitHubLibrary::new(config);
+
+        library.workflow("test").await.unwrap();
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn workflow_with_missing_app_key() {
+        let config = GitHubConfiguration::builder()
+            .owner("owner")
+            .repository("name")
+            .authentication(Authentication::App(
+                AppAuthentication::builder()
+                    .id(42)
+                    .key_file("/does/not/exist.pem")
+                    .build(),
+            ))
+            .build();
+        let library = GitHubLibrary::new(config);
+
+        let error = library.workflow("test").await.unwrap_err();
+
+        assert!(matches!(error, Error::Authentication(_)));
+    }
+
     #[test]
     fn base64_decode_ok() {
         let base64_encoded_string =
@@ -263,6 +1154,7 @@ mod tests {
     #[tokio::test]
     async fn fetch_from_github() {
         let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
         let mock = server
             .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
             .match_query(mockito::Matcher::Any)
@@ -274,9 +1166,10 @@ mod tests {
         let config = build_config(&server.url());
         let library = GitHubLibrary::new(config);
 
-        let content = library
-            .fetch_from_github("test/workflow.yml")
+        let (content, _) = library
+            .fetch_from_github("test/workflow.yml", None)
             .await
+            .unwrap()
             .unwrap();
 
         mock.assert();
@@ -286,6 +1179,7 @@ mod tests {
     #[tokio::test]
     async fn fetch_from_github_not_found() {
         let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
         let mock = server
             .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
             .match_query(mockito::Matcher::Any)
@@ -305,7 +1199,7 @@ mod tests {
         let library = GitHubLibrary::new(config);
 
         let error = library
-            .fetch_from_github("test/workflow.yml")
+            .fetch_from_github("test/workflow.yml", None)
             .await
             .unwrap_err();
 
@@ -313,6 +1207,195 @@ mod tests {
         assert!(matches!(error, Error::NotFound(_, _)));
     }
 
+    #[tokio::test]
+    async fn fetch_from_github_unknown_reference() {
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(indoc!(
+                r#"
+                {
+                    "message": "No commit found for the ref v2",
+                    "documentation_url": "https://docs.github.com/rest/repos/contents#get-repository-content"
+                }
+                "#
+            ))
+            .create();
+
+        let mut config = build_config(&server.url());
+        config.set_reference("v2");
+        let library = GitHubLibrary::new(config);
+
+        let error = library
+            .fetch_from_github("test/workflow.yml", None)
+            .await
+            .unwrap_err();
+
+        mock.assert();
+        assert!(matches!(error, Error::UnknownReference(reference, _) if reference == "v2"));
+    }
+
+    #[tokio::test]
+    async fn fetch_from_github_unauthorized() {
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(401)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(r#"{"message": "Bad credentials"}"#)
+            .create();
+
+        let library = GitHubLibrary::new(build_config(&server.url()));
+
+        let error = library
+            .fetch_from_github("test/workflow.yml", None)
+            .await
+            .unwrap_err();
+
+        mock.assert();
+        assert!(matches!(error, Error::Authentication(_)));
+    }
+
+    #[tokio::test]
+    async fn fetch_from_github_rate_limited() {
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(r#"{"message": "API rate limit exceeded"}"#)
+            .create();
+
+        let library = GitHubLibrary::new(build_config(&server.url()));
+
+        let error = library
+            .fetch_from_github("test/workflow.yml", None)
+            .await
+            .unwrap_err();
+
+        mock.assert();
+        assert!(matches!(error, Error::GitHub(_)));
+    }
+
+    #[tokio::test]
+    async fn jobs_unauthorized() {
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/rust")
+            .match_query(mockito::Matcher::Any)
+            .with_status(401)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_body(r#"{"message": "Bad credentials"}"#)
+            .create();
+
+        let library = GitHubLibrary::new(build_config(&server.url()));
+
+        let error = library.jobs("rust").await.unwrap_err();
+
+        mock.assert();
+        assert!(matches!(error, Error::Authentication(_)));
+    }
+
+    #[tokio::test]
+    async fn workflow_is_cached() {
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        mock_directory(&mut server, "test", &["workflow.yml"]);
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json; charset=utf-8")
+            .with_header("etag", "\"abc\"")
+            .with_body(WORKFLOW_RESPONSE)
+            .create();
+
+        let library =
+            GitHubLibrary::new(build_config(&server.url())).cache(Cache::new(cache_dir.path()));
+        library.workflow("test").await.unwrap();
+
+        mock.assert();
+
+        let offline_library = GitHubLibrary::new(build_config(&server.url()))
+            .cache(Cache::new(cache_dir.path()))
+            .offline(true);
+        let workflow = offline_library.workflow("test").await.unwrap();
+
+        assert_eq!(&Template::new(WORKFLOW), workflow.template());
+        assert_eq!(
+            Some("3d21ec53a331a6f037a91c368710b99387d012c1"),
+            workflow.revision()
+        );
+    }
+
+    #[tokio::test]
+    async fn workflow_not_modified_is_read_from_cache() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(cache_dir.path());
+
+        let mut server = mockito::Server::new_async().await;
+        mock_commit(&mut server);
+        mock_directory(&mut server, "test", &["workflow.yml"]);
+        let library = GitHubLibrary::new(build_config(&server.url())).cache(cache.clone());
+
+        cache
+            .insert(
+                &library.cache_key(Some(COMMIT), "test/workflow.yml"),
+                &CacheEntry::new("abc", Some("\"abc\"".into()), WORKFLOW),
+            )
+            .unwrap();
+
+        let mock = server
+            .mock("GET", "/repos/owner/name/contents/test/workflow.yml")
+            .match_query(mockito::Matcher::Any)
+            .match_header("if-none-match", "\"abc\"")
+            .with_status(304)
+            .create();
+
+        let workflow = library.workflow("test").await.unwrap();
+
+        mock.assert();
+        assert_eq!(&Template::new(WORKFLOW), workflow.template());
+        assert_eq!(Some("abc"), workflow.revision());
+    }
+
+    #[tokio::test]
+    async fn workflow_offline_without_cache() {
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        let library = GitHubLibrary::new(build_config("http://localhost"))
+            .cache(Cache::new(cache_dir.path()))
+            .offline(true);
+
+        let error = library.workflow("test").await.unwrap_err();
+
+        assert!(matches!(error, Error::NotCached(_, _)));
+    }
+
+    #[tokio::test]
+    async fn clones_share_request_limit() {
+        let library = GitHubLibrary::new(build_config("http://localhost"));
+        let clone = library.clone();
+
+        let _permit = library.permit().await;
+
+        assert_eq!(
+            MAX_CONCURRENT_REQUESTS - 1,
+            clone.requests.available_permits()
+        );
+    }
+
     #[test]
     fn trait_display() {
         let configuration = GitHubConfiguration::builder()
@@ -325,6 +1408,19 @@ mod tests {
         assert_eq!("repository jdno/flowcrafter", library.to_string());
     }
 
+    #[test]
+    fn trait_display_with_reference() {
+        let configuration = GitHubConfiguration::builder()
+            .owner(Owner::from("jdno"))
+            .repository(Repository::from("flowcrafter"))
+            .reference("v1.0.0")
+            .build();
+
+        let library = GitHubLibrary::new(configuration);
+
+        assert_eq!("repository jdno/flowcrafter@v1.0.0", library.to_string());
+    }
+
     #[test]
     fn trait_send() {
         fn assert_send<T: Send>() {}
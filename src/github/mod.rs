@@ -1,8 +1,12 @@
 pub use self::{
-    configuration::GitHubConfiguration, library::GitHubLibrary, owner::Owner,
+    authentication::{AppAuthentication, Authentication},
+    configuration::GitHubConfiguration,
+    library::GitHubLibrary,
+    owner::Owner,
     repository::Repository,
 };
 
+mod authentication;
 mod configuration;
 mod library;
 mod owner;
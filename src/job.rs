@@ -0,0 +1,220 @@
+use std::fmt::{Display, Formatter};
+
+use typed_builder::TypedBuilder;
+
+use crate::error::Error;
+use crate::fragment::{job_reference_name, Fragment};
+use crate::matrix::Matrix;
+use crate::variable::Variables;
+
+/// A job fragment together with the values that it is rendered with
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, TypedBuilder)]
+pub struct Job {
+    fragment: Fragment,
+    #[builder(default)]
+    id: Option<String>,
+    #[builder(default)]
+    variables: Variables,
+    #[builder(default)]
+    matrix: Matrix,
+    #[builder(default)]
+    steps: Vec<Fragment>,
+}
+
+impl Job {
+    pub fn fragment(&self) -> &Fragment {
+        &self.fragment
+    }
+
+    /// Returns the id that replaces the key of the job in the rendered workflow
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn variables(&self) -> &Variables {
+        &self.variables
+    }
+
+    /// Returns the matrix that is added to the `strategy.matrix` of the job
+    pub fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    /// Returns the steps that the job references, which are spliced into its `steps` when rendered
+    pub fn steps(&self) -> &[Fragment] {
+        &self.steps
+    }
+}
+
+impl Display for Job {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.fragment)
+    }
+}
+
+impl From<Fragment> for Job {
+    fn from(fragment: Fragment) -> Self {
+        Self::builder().fragment(fragment).build()
+    }
+}
+
+/// Orders jobs so that every job comes after the jobs that it requires
+///
+/// Jobs keep the order in which they were given, unless one of their requirements has to be moved
+/// in front of them. Requirements that are not in the list are ignored, since they can refer to
+/// jobs in the workflow template.
+pub fn sort_by_dependencies(jobs: Vec<Job>) -> Result<Vec<Job>, Error> {
+    let mut sorted = Vec::with_capacity(jobs.len());
+    let mut visited = vec![false; jobs.len()];
+    let mut path = Vec::new();
+
+    for index in 0..jobs.len() {
+        visit(&jobs, index, &mut visited, &mut path, &mut sorted)?;
+    }
+
+    let mut jobs: Vec<Option<Job>> = jobs.into_iter().map(Some).collect();
+
+    Ok(sorted
+        .into_iter()
+        .filter_map(|index| jobs[index].take())
+        .collect())
+}
+
+/// Visits the requirements of a job depth-first, and then adds the job itself to the sorted list
+fn visit(
+    jobs: &[Job],
+    index: usize,
+    visited: &mut [bool],
+    path: &mut Vec<usize>,
+    sorted: &mut Vec<usize>,
+) -> Result<(), Error> {
+    if visited[index] {
+        return Ok(());
+    }
+
+    if let Some(start) = path.iter().position(|job| *job == index) {
+        let cycle = path[start..]
+            .iter()
+            .chain([&index])
+            .map(|job| jobs[*job].fragment().name().to_string())
+            .collect();
+
+        return Err(Error::DependencyCycle(cycle));
+    }
+
+    path.push(index);
+
+    for requirement in jobs[index].fragment().metadata().requires() {
+        let name = job_reference_name(requirement);
+
+        // A job that has been added more than once is required in all of its instances
+        let requirements: Vec<usize> = (0..jobs.len())
+            .filter(|job| jobs[*job].fragment().name() == name)
+            .collect();
+
+        for requirement in requirements {
+            visit(jobs, requirement, visited, path, sorted)?;
+        }
+    }
+
+    path.pop();
+    visited[index] = true;
+    sorted.push(index);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(name: &str, requires: &[&str]) -> Job {
+        let content = format!(
+            "---\nrequires: [{}]\n---\n{name}:\n  runs-on: ubuntu-latest\n",
+            requires.join(", ")
+        );
+
+        Fragment::parse(name, &content).unwrap().into()
+    }
+
+    fn names(jobs: &[Job]) -> Vec<&str> {
+        jobs.iter().map(|job| job.fragment().name()).collect()
+    }
+
+    #[test]
+    fn sort_by_dependencies_moves_requirements_first() {
+        let jobs = vec![
+            job("deploy", &["test"]),
+            job("lint", &[]),
+            job("test", &["build"]),
+            job("build", &[]),
+        ];
+
+        let sorted = sort_by_dependencies(jobs).unwrap();
+
+        assert_eq!(vec!["build", "test", "deploy", "lint"], names(&sorted));
+    }
+
+    #[test]
+    fn sort_by_dependencies_ignores_unknown_requirements() {
+        let jobs = vec![job("test", &["detect-changes"]), job("lint", &[])];
+
+        let sorted = sort_by_dependencies(jobs).unwrap();
+
+        assert_eq!(vec!["test", "lint"], names(&sorted));
+    }
+
+    #[test]
+    fn sort_by_dependencies_errors_on_cycle() {
+        let jobs = vec![
+            job("build", &["deploy"]),
+            job("test", &["build"]),
+            job("deploy", &["test"]),
+        ];
+
+        let error = sort_by_dependencies(jobs).unwrap_err();
+
+        assert_eq!(
+            "jobs depend on each other in a cycle: build -> deploy -> test -> build",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn from_fragment() {
+        let fragment = Fragment::builder()
+            .name("test")
+            .template("test: {}".into())
+            .build();
+
+        let job = Job::from(fragment.clone());
+
+        assert_eq!(&fragment, job.fragment());
+        assert!(job.variables().is_empty());
+    }
+
+    #[test]
+    fn trait_display() {
+        let job = Job::from(Fragment::builder().name("test").template("".into()).build());
+
+        assert_eq!("test", job.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Job>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Job>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Job>();
+    }
+}
@@ -1,13 +1,24 @@
-pub use self::{error::*, fragment::*, project::*, renderer::*, template::*, workflow::*};
+pub use self::{
+    cache::*, error::*, fragment::*, job::*, matrix::*, project::*, renderer::*, template::*,
+    validation::*, variable::*, workflow::*,
+};
 
 #[cfg(feature = "cli")]
 pub mod cli;
 
+pub mod archive;
+pub mod builtin;
+mod cache;
 mod error;
 mod fragment;
+pub mod git;
 pub mod github;
+mod job;
 pub mod local;
+mod matrix;
 mod project;
 mod renderer;
 mod template;
+mod validation;
+mod variable;
 mod workflow;
@@ -2,9 +2,11 @@ use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 
 use async_trait::async_trait;
+use sha2::{Digest, Sha256};
 
+use crate::fragment::{job_name, step_paths};
 use crate::local::LocalConfiguration;
-use crate::{Error, Fragment, FragmentLibrary, Project, Template};
+use crate::{Error, Fragment, FragmentLibrary, Project};
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct LocalLibrary {
@@ -18,7 +20,12 @@ impl LocalLibrary {
         Self { path }
     }
 
-    fn read_template(&self, path: &PathBuf) -> Result<Template, Error> {
+    /// Creates a library for the templates in a directory, e.g. a checkout of a repository
+    pub(crate) fn at(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn read_fragment(&self, name: &str, path: &PathBuf) -> Result<Fragment, Error> {
         if !path.exists() {
             return Err(Error::NotFound(
                 path.file_name()
@@ -30,7 +37,18 @@ impl LocalLibrary {
             ));
         }
 
-        Ok(std::fs::read_to_string(path)?.into())
+        let content = std::fs::read_to_string(path)?;
+
+        let sidecar_path = path.with_extension("meta.yml");
+        let sidecar = match sidecar_path.is_file() {
+            true => Some(std::fs::read_to_string(sidecar_path)?),
+            false => None,
+        };
+
+        let mut fragment = Fragment::parse_with_sidecar(name, &content, sidecar.as_deref())?;
+        fragment.set_revision(content_hash(&content, sidecar.as_deref()));
+
+        Ok(fragment)
     }
 }
 
@@ -44,17 +62,78 @@ impl Display for LocalLibrary {
 impl<'a> FragmentLibrary<'a> for LocalLibrary {
     async fn workflow(&self, name: &'a str) -> Result<Fragment, Error> {
         let path = self.path.join(name).join("workflow.yml");
-        let template = self.read_template(&path)?;
-
-        Ok(Fragment::builder().name(name).template(template).build())
+        self.read_fragment(name, &path)
     }
 
     async fn job(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error> {
         let path = self.path.join(workflow).join(format!("{name}.yml"));
-        let template = self.read_template(&path)?;
+        self.read_fragment(name, &path)
+    }
 
-        Ok(Fragment::builder().name(name).template(template).build())
+    async fn workflows(&self) -> Result<Vec<String>, Error> {
+        let mut workflows = Vec::new();
+
+        for entry in std::fs::read_dir(&self.path)? {
+            let entry = entry?;
+
+            if !entry.path().join("workflow.yml").is_file() {
+                continue;
+            }
+            if let Some(name) = entry.file_name().to_str() {
+                workflows.push(name.to_string());
+            }
+        }
+
+        workflows.sort();
+        Ok(workflows)
     }
+
+    async fn jobs(&self, workflow: &'a str) -> Result<Vec<String>, Error> {
+        let path = self.path.join(workflow);
+        if !path.join("workflow.yml").is_file() {
+            return Err(Error::NotFound(workflow.into(), self.to_string()));
+        }
+
+        let mut jobs = Vec::new();
+
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+
+            if !entry.path().is_file() {
+                continue;
+            }
+            if let Some(name) = entry.file_name().to_str().and_then(job_name) {
+                jobs.push(name.to_string());
+            }
+        }
+
+        jobs.sort();
+        Ok(jobs)
+    }
+
+    async fn step(&self, workflow: &'a str, name: &'a str) -> Result<Fragment, Error> {
+        let [workflow_step, shared_step] = step_paths(workflow, name);
+
+        match self.read_fragment(name, &self.path.join(workflow_step)) {
+            Err(Error::NotFound(_, _)) => self.read_fragment(name, &self.path.join(shared_step)),
+            result => result,
+        }
+    }
+}
+
+/// Returns the SHA-256 hash of a template and its sidecar, which is used as its revision
+fn content_hash(content: &str, sidecar: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    if let Some(sidecar) = sidecar {
+        hasher.update(sidecar.as_bytes());
+    }
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
 }
 
 #[cfg(test)]
@@ -116,6 +195,33 @@ mod tests {
         let workflow = library.workflow("workflow").await.unwrap();
 
         assert_eq!(workflow.template().get(), "name: workflow");
+        assert_eq!(
+            Some("f4bc5f73cb19054852a0e362ffd13fe4ec4b9451c9022473675fba5bac49395d"),
+            workflow.revision()
+        );
+    }
+
+    #[tokio::test]
+    async fn job_with_sidecar() {
+        let test_project = TestProject::new().unwrap();
+
+        create_dir(test_project.path().join("rust")).unwrap();
+        std::fs::write(test_project.path().join("rust/test.yml"), "test:\n").unwrap();
+        std::fs::write(
+            test_project.path().join("rust/test.meta.yml"),
+            "description: Run the tests\n",
+        )
+        .unwrap();
+
+        let library = LocalLibrary::new(
+            test_project.project(),
+            &LocalConfiguration::builder().path(".").build(),
+        );
+
+        let job = library.job("rust", "test").await.unwrap();
+
+        assert_eq!("test:\n", job.template().get());
+        assert_eq!(Some("Run the tests"), job.metadata().description());
     }
 
     #[tokio::test]
@@ -138,6 +244,109 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn workflows() {
+        let test_project = TestProject::new().unwrap();
+
+        for workflow in ["rust", "markdown"] {
+            create_dir(test_project.path().join(workflow)).unwrap();
+            std::fs::write(
+                test_project.path().join(workflow).join("workflow.yml"),
+                "name: workflow",
+            )
+            .unwrap();
+        }
+        create_dir(test_project.path().join("docs")).unwrap();
+
+        let library = LocalLibrary::new(
+            test_project.project(),
+            &LocalConfiguration::builder().path(".").build(),
+        );
+
+        let workflows = library.workflows().await.unwrap();
+
+        assert_eq!(vec!["markdown", "rust"], workflows);
+    }
+
+    #[tokio::test]
+    async fn jobs() {
+        let test_project = TestProject::new().unwrap();
+
+        create_dir(test_project.path().join("rust")).unwrap();
+        for file in [
+            "workflow.yml",
+            "test.yml",
+            "lint.yml",
+            "lint.meta.yml",
+            "README.md",
+        ] {
+            std::fs::write(test_project.path().join("rust").join(file), "").unwrap();
+        }
+
+        let library = LocalLibrary::new(
+            test_project.project(),
+            &LocalConfiguration::builder().path(".").build(),
+        );
+
+        let jobs = library.jobs("rust").await.unwrap();
+
+        assert_eq!(vec!["lint", "test"], jobs);
+    }
+
+    #[tokio::test]
+    async fn step_from_workflow_before_shared_steps() {
+        let test_project = TestProject::new().unwrap();
+
+        std::fs::create_dir_all(test_project.path().join("rust/steps")).unwrap();
+        create_dir(test_project.path().join("steps")).unwrap();
+        for (path, content) in [
+            ("rust/steps/setup.yml", "- run: rustup show\n"),
+            ("steps/setup.yml", "- run: echo setup\n"),
+            ("steps/checkout.yml", "- uses: actions/checkout@v4\n"),
+        ] {
+            std::fs::write(test_project.path().join(path), content).unwrap();
+        }
+
+        let library = LocalLibrary::new(
+            test_project.project(),
+            &LocalConfiguration::builder().path(".").build(),
+        );
+
+        let setup = library.step("rust", "setup").await.unwrap();
+        let checkout = library.step("rust", "checkout").await.unwrap();
+
+        assert_eq!("- run: rustup show\n", setup.template().get());
+        assert_eq!("- uses: actions/checkout@v4\n", checkout.template().get());
+    }
+
+    #[tokio::test]
+    async fn step_not_found() {
+        let test_project = TestProject::new().unwrap();
+
+        let library = LocalLibrary::new(
+            test_project.project(),
+            &LocalConfiguration::builder().path(".").build(),
+        );
+
+        let error = library.step("rust", "setup").await.unwrap_err();
+
+        assert!(matches!(error, Error::NotFound(_, _)));
+    }
+
+    #[tokio::test]
+    async fn jobs_of_missing_workflow() {
+        let test_project = TestProject::new().unwrap();
+
+        let library = LocalLibrary::new(
+            test_project.project(),
+            &LocalConfiguration::builder().path(".").build(),
+        );
+
+        let error = library.jobs("rust").await.unwrap_err();
+
+        assert!(matches!(error, Error::NotFound(_, _)));
+    }
+
     #[test]
     fn test_display() {
         let test_project = TestProject::new().unwrap();
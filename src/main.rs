@@ -12,5 +12,5 @@ async fn main() -> Result<(), Error> {
     let cwd = std::env::current_dir().context("failed to detect current directory")?;
     let project = Project::find(cwd)?;
 
-    Commands::execute(&cli.command, &project).await
+    Commands::execute(&cli.command, &project, cli.offline).await
 }
@@ -0,0 +1,302 @@
+use std::collections::BTreeMap;
+
+use serde_yaml::{Mapping, Value};
+
+use crate::error::Error;
+
+/// A build matrix that is added to the `strategy.matrix` of a job when it is rendered
+///
+/// Each dimension of the matrix is a list of values, e.g. operating systems or toolchains. The
+/// combinations in `include` and `exclude` are appended to the ones that the job already declares.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Matrix {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    dimensions: BTreeMap<String, Vec<String>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    include: Vec<BTreeMap<String, String>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    exclude: Vec<BTreeMap<String, String>>,
+}
+
+impl Matrix {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dimension(&self, name: &str) -> Option<&[String]> {
+        self.dimensions.get(name).map(Vec::as_slice)
+    }
+
+    /// Adds a dimension to the matrix, replacing an existing one with the same name
+    pub fn insert(&mut self, name: impl Into<String>, values: Vec<String>) {
+        self.dimensions.insert(name.into(), values);
+    }
+
+    pub fn include(&self) -> &[BTreeMap<String, String>] {
+        &self.include
+    }
+
+    pub fn exclude(&self) -> &[BTreeMap<String, String>] {
+        &self.exclude
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dimensions.is_empty() && self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Merges the matrix into the matrix that a job declares
+    ///
+    /// Dimensions cannot be declared by both, since it would be unclear which values to use.
+    /// Combinations in `include` and `exclude` are appended to the ones of the job.
+    pub(crate) fn merge(&self, job: &str, matrix: Option<&Value>) -> Result<Value, Error> {
+        let mut merged = match matrix {
+            None | Some(Value::Null) => Mapping::new(),
+            Some(Value::Mapping(matrix)) => matrix.clone(),
+            Some(_) => {
+                return Err(Error::Render(format!(
+                    "matrix of job '{job}' must be a mapping to add a matrix to it"
+                )))
+            }
+        };
+
+        for (name, values) in &self.dimensions {
+            if merged.contains_key(name.as_str()) {
+                return Err(Error::Render(format!(
+                    "matrix of job '{job}' already declares '{name}'"
+                )));
+            }
+
+            merged.insert(
+                name.as_str().into(),
+                values
+                    .iter()
+                    .map(|value| Value::from(value.as_str()))
+                    .collect(),
+            );
+        }
+
+        for (key, combinations) in [("include", &self.include), ("exclude", &self.exclude)] {
+            if combinations.is_empty() {
+                continue;
+            }
+
+            let existing = match merged.remove(key) {
+                None | Some(Value::Null) => Vec::new(),
+                Some(Value::Sequence(existing)) => existing,
+                Some(_) => {
+                    return Err(Error::Render(format!(
+                        "'{key}' in the matrix of job '{job}' must be a list to add to it"
+                    )))
+                }
+            };
+
+            let combinations = combinations.iter().map(|combination| {
+                Value::Mapping(
+                    combination
+                        .iter()
+                        .map(|(name, value)| (name.as_str().into(), value.as_str().into()))
+                        .collect(),
+                )
+            });
+
+            merged.insert(
+                key.into(),
+                existing.into_iter().chain(combinations).collect(),
+            );
+        }
+
+        Ok(Value::Mapping(merged))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Matrix {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+
+        let values = BTreeMap::<String, Value>::deserialize(deserializer)?;
+        let mut matrix = Matrix::new();
+
+        for (name, value) in values {
+            match name.as_str() {
+                "include" | "exclude" => {
+                    let combinations = value
+                        .as_sequence()
+                        .map(|combinations| {
+                            combinations.iter().map(combination).collect::<Option<_>>()
+                        })
+                        .unwrap_or_default()
+                        .ok_or_else(|| {
+                            D::Error::custom(format!(
+                                "matrix '{name}' must be a list of mappings with scalar values"
+                            ))
+                        })?;
+
+                    if name == "include" {
+                        matrix.include = combinations;
+                    } else {
+                        matrix.exclude = combinations;
+                    }
+                }
+                _ => {
+                    let values = value
+                        .as_sequence()
+                        .map(|values| values.iter().map(scalar).collect::<Option<_>>())
+                        .unwrap_or_default()
+                        .ok_or_else(|| {
+                            D::Error::custom(format!(
+                                "matrix dimension '{name}' must be a list of scalar values"
+                            ))
+                        })?;
+
+                    matrix.dimensions.insert(name, values);
+                }
+            }
+        }
+
+        Ok(matrix)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn combination(value: &Value) -> Option<BTreeMap<String, String>> {
+    value
+        .as_mapping()?
+        .iter()
+        .map(|(name, value)| Some((name.as_str()?.to_string(), scalar(value)?)))
+        .collect()
+}
+
+#[cfg(feature = "serde")]
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(value) => Some(value.to_string()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::String(value) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn matrix() -> Matrix {
+        let mut matrix = Matrix::new();
+        matrix.insert("toolchain", vec!["stable".into(), "nightly".into()]);
+        matrix
+    }
+
+    #[test]
+    fn merge_adds_dimensions() {
+        let existing: Value = serde_yaml::from_str("os: [ubuntu-latest]").unwrap();
+
+        let merged = matrix().merge("test", Some(&existing)).unwrap();
+
+        assert_eq!(
+            "os:\n- ubuntu-latest\ntoolchain:\n- stable\n- nightly\n",
+            serde_yaml::to_string(&merged).unwrap()
+        );
+    }
+
+    #[test]
+    fn merge_errors_on_conflicting_dimension() {
+        let existing: Value = serde_yaml::from_str("toolchain: [beta]").unwrap();
+
+        let error = matrix().merge("test", Some(&existing)).unwrap_err();
+
+        assert_eq!(
+            "failed to render workflow: matrix of job 'test' already declares 'toolchain'",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn merge_errors_on_expression() {
+        let existing = Value::String("${{ fromJSON(needs.setup.outputs.matrix) }}".into());
+
+        assert!(matrix().merge("test", Some(&existing)).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn merge_appends_combinations() {
+        let matrix: Matrix =
+            serde_yaml::from_str("include:\n  - os: windows-latest\n    toolchain: stable\n")
+                .unwrap();
+        let existing: Value = serde_yaml::from_str("include: [{os: macos-latest}]").unwrap();
+
+        let merged = matrix.merge("test", Some(&existing)).unwrap();
+
+        assert_eq!(
+            "include:\n- os: macos-latest\n- os: windows-latest\n  toolchain: stable\n",
+            serde_yaml::to_string(&merged).unwrap()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize() {
+        let yaml = indoc!(
+            r#"
+            os: [ubuntu-latest, macos-latest]
+            toolchain: ["1.70", stable]
+            exclude:
+              - os: macos-latest
+                toolchain: "1.70"
+            "#
+        );
+
+        let matrix: Matrix = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(
+            Some(&["1.70".into(), "stable".into()][..]),
+            matrix.dimension("toolchain")
+        );
+        assert_eq!(
+            Some("macos-latest"),
+            matrix.exclude()[0].get("os").map(String::as_str)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_errors_on_nested_values() {
+        let result = serde_yaml::from_str::<Matrix>("os: [[ubuntu-latest]]");
+
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_serialize() {
+        assert_eq!(
+            "toolchain:\n- stable\n- nightly\n",
+            serde_yaml::to_string(&matrix()).unwrap()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Matrix>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Matrix>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Matrix>();
+    }
+}
@@ -0,0 +1,841 @@
+use serde_yaml::Value;
+
+use crate::error::Error;
+
+const DEFAULT_INDENT: usize = 2;
+
+/// A YAML document that is edited line by line
+///
+/// The document is parsed once to make sure that it is valid YAML, but all modifications are made
+/// on the original lines. This preserves comments, blank lines, and the order of keys, which would
+/// all be lost when round-tripping the document through a YAML serializer.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Document {
+    lines: Vec<String>,
+}
+
+/// An entry in a block mapping, spanning from the line with its key to the last line of its value
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Entry {
+    line: usize,
+    indent: usize,
+    end: usize,
+}
+
+impl Entry {
+    /// Returns the number of the line with the key of the entry, starting at one
+    pub fn line_number(&self) -> usize {
+        self.line + 1
+    }
+}
+
+impl Document {
+    pub fn parse(name: &str, source: &str) -> Result<Self, Error> {
+        let value: Value = serde_yaml::from_str(source)
+            .map_err(|error| Error::Render(format!("failed to parse '{name}': {error}")))?;
+
+        if !matches!(value, Value::Mapping(_) | Value::Null) {
+            return Err(Error::Render(format!("'{name}' must be a YAML mapping")));
+        }
+
+        let lines = source.lines().map(String::from).collect();
+
+        Ok(Self { lines })
+    }
+
+    /// Returns the top-level entries of the document
+    pub fn entries(&self) -> Vec<Entry> {
+        self.entries_in(0, self.lines.len())
+    }
+
+    /// Returns the entries of the block mapping that is the value of the given entry
+    pub fn children(&self, parent: &Entry) -> Vec<Entry> {
+        self.entries_in(parent.line + 1, parent.end)
+    }
+
+    /// Returns the lines of the items of the block sequence that is the value of an entry
+    pub fn items(&self, parent: &Entry) -> Vec<usize> {
+        let Some(indent) = self.lines[parent.line + 1..parent.end]
+            .iter()
+            .find(|line| is_content(line))
+            .map(|line| indentation(line))
+        else {
+            return Vec::new();
+        };
+
+        (parent.line + 1..parent.end)
+            .filter(|index| {
+                let line = &self.lines[*index];
+                let item = line.trim_start();
+
+                indentation(line) == indent && (item == "-" || item.starts_with("- "))
+            })
+            .collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<Entry> {
+        self.entries()
+            .into_iter()
+            .find(|entry| self.key(entry) == key)
+    }
+
+    pub fn key(&self, entry: &Entry) -> String {
+        parse_key(self.lines[entry.line].trim_start())
+            .map(|(key, _)| key)
+            .unwrap_or_default()
+    }
+
+    /// Returns the value that is written on the same line as the key, without trailing comments
+    pub fn inline_value(&self, entry: &Entry) -> &str {
+        let line = self.lines[entry.line].trim_start();
+
+        match parse_key(line) {
+            Some((_, offset)) => strip_comment(&line[offset..]).trim(),
+            None => "",
+        }
+    }
+
+    /// Returns the indentation that is used for the children of an entry
+    pub fn child_indent(&self, entry: &Entry) -> usize {
+        self.lines[entry.line + 1..entry.end]
+            .iter()
+            .find(|line| is_content(line))
+            .map(|line| indentation(line))
+            .unwrap_or(entry.indent + DEFAULT_INDENT)
+    }
+
+    /// Turns the value of an entry into an empty block mapping that children can be appended to
+    ///
+    /// Values that are written in flow style on the same line as the key (e.g. `jobs: {}`) are
+    /// rewritten as a block mapping, since entries cannot be appended to them otherwise. An anchor
+    /// or tag on the line of the key is kept, and does not make the value a flow collection.
+    pub fn expand(&mut self, entry: &Entry, value: &Value) -> Result<Entry, Error> {
+        let inline_value = self.inline_value(entry).to_string();
+        let (properties, inline_value) = split_properties(&inline_value);
+        let key_line = self.lines[entry.line].clone();
+        let key_length = key_line.len() - key_line.trim_start().len()
+            + parse_key(key_line.trim_start())
+                .map(|(_, offset)| offset)
+                .unwrap_or_default();
+
+        if inline_value.is_empty() {
+            return Ok(*entry);
+        }
+
+        let is_flow = inline_value.starts_with(['{', '[']);
+        let expanded = match value {
+            Value::Null => Vec::new(),
+            Value::Mapping(mapping) if is_flow && mapping.is_empty() => Vec::new(),
+            Value::Mapping(_) if is_flow => {
+                let serialized = serde_yaml::to_string(value)
+                    .map_err(|error| Error::Render(error.to_string()))?;
+
+                reindent(&serialized, entry.indent + DEFAULT_INDENT)
+            }
+            Value::Mapping(_) => {
+                return Err(Error::Render(format!(
+                    "'{}' must be a block or flow mapping to be extended",
+                    self.key(entry)
+                )))
+            }
+            _ => {
+                return Err(Error::Render(format!(
+                    "'{}' must be a mapping",
+                    self.key(entry)
+                )))
+            }
+        };
+
+        self.lines[entry.line] = match properties {
+            "" => key_line[..key_length].to_string(),
+            properties => format!("{} {properties}", &key_line[..key_length]),
+        };
+
+        let inserted = expanded.len();
+        self.lines.splice(entry.line + 1..entry.line + 1, expanded);
+
+        Ok(Entry {
+            end: entry.end + inserted,
+            ..*entry
+        })
+    }
+
+    /// Appends a new entry to the top level of the document
+    pub fn push(&mut self, key: &str) -> Entry {
+        while self.lines.last().is_some_and(|line| line.trim().is_empty()) {
+            self.lines.pop();
+        }
+
+        if !self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+
+        self.lines.push(format!("{key}:"));
+
+        Entry {
+            line: self.lines.len() - 1,
+            indent: 0,
+            end: self.lines.len(),
+        }
+    }
+
+    /// Appends a block of YAML to the end of the value of an entry
+    ///
+    /// The block is indented to match the existing children of the entry, and separated from them
+    /// by an empty line.
+    pub fn append(&mut self, entry: &Entry, block: &str) -> Entry {
+        let indent = self.child_indent(entry);
+        let mut lines = reindent(block, indent);
+
+        if !self.children(entry).is_empty() {
+            lines.insert(0, String::new());
+        }
+
+        let inserted = lines.len();
+        self.lines.splice(entry.end..entry.end, lines);
+
+        Entry {
+            end: entry.end + inserted,
+            ..*entry
+        }
+    }
+
+    /// Sets a child of an entry to a value that is written on a single line
+    ///
+    /// An existing child is replaced. Otherwise, the child is inserted after the `name` of the
+    /// entry if it has one, or as its first child.
+    pub fn set(&mut self, parent: &Entry, key: &str, value: &str) -> Entry {
+        let line = format!("{}{key}: {value}", " ".repeat(self.child_indent(parent)));
+
+        self.set_lines(parent, key, vec![line])
+    }
+
+    /// Sets a child of an entry to a mapping or sequence that is written in block style
+    ///
+    /// The child is replaced or inserted in the same place as with [`Document::set`].
+    pub fn set_block(&mut self, parent: &Entry, key: &str, value: &Value) -> Result<Entry, Error> {
+        let indent = self.child_indent(parent);
+        let serialized =
+            serde_yaml::to_string(value).map_err(|error| Error::Render(error.to_string()))?;
+
+        let mut lines = vec![format!("{}{key}:", " ".repeat(indent))];
+        lines.extend(reindent(&serialized, indent + DEFAULT_INDENT));
+
+        Ok(self.set_lines(parent, key, lines))
+    }
+
+    fn set_lines(&mut self, parent: &Entry, key: &str, lines: Vec<String>) -> Entry {
+        let children = self.children(parent);
+
+        let (range, removed) = match children.iter().find(|child| self.key(child) == key) {
+            Some(child) => (child.line..child.end, child.end - child.line),
+            None => {
+                let position = children
+                    .first()
+                    .filter(|child| self.key(child) == "name")
+                    .map(|child| child.end)
+                    .unwrap_or(parent.line + 1);
+
+                (position..position, 0)
+            }
+        };
+
+        let inserted = lines.len();
+        self.lines.splice(range, lines);
+
+        Entry {
+            end: parent.end + inserted - removed,
+            ..*parent
+        }
+    }
+
+    /// Replaces an item of the block sequence that is the value of an entry with a block of YAML
+    ///
+    /// The block is indented to match the item, so that a block sequence is spliced into the
+    /// sequence of the entry.
+    pub fn replace_item(&mut self, parent: &Entry, item: usize, block: &str) -> Entry {
+        let indent = indentation(&self.lines[item]);
+
+        let mut end = self
+            .items(parent)
+            .into_iter()
+            .find(|line| *line > item)
+            .unwrap_or(parent.end);
+        while end > item + 1 && !self.belongs_to(end - 1, indent) {
+            end -= 1;
+        }
+
+        let lines = reindent(block, indent);
+        let inserted = lines.len();
+        self.lines.splice(item..end, lines);
+
+        Entry {
+            end: parent.end + inserted - (end - item),
+            ..*parent
+        }
+    }
+
+    /// Changes the key of an entry, keeping its value and any comment on the same line
+    pub fn rename(&mut self, entry: &Entry, key: &str) {
+        let line = &self.lines[entry.line];
+        let value_offset = parse_key(line.trim_start())
+            .map(|(_, offset)| offset)
+            .unwrap_or_default();
+
+        self.lines[entry.line] = format!(
+            "{}{key}:{}",
+            " ".repeat(entry.indent),
+            &line.trim_start()[value_offset..]
+        );
+    }
+
+    /// Removes an entry from the document, together with the empty line that separates it from
+    /// the entry before it
+    pub fn remove(&mut self, entry: &Entry) {
+        let mut start = entry.line;
+        while start > 0 && self.lines[start - 1].trim().is_empty() {
+            start -= 1;
+        }
+
+        let mut end = entry.end;
+        if start == entry.line {
+            while end < self.lines.len() && self.lines[end].trim().is_empty() {
+                end += 1;
+            }
+        }
+
+        self.lines.drain(start..end);
+    }
+
+    fn entries_in(&self, start: usize, end: usize) -> Vec<Entry> {
+        let Some(indent) = self.lines[start..end]
+            .iter()
+            .find(|line| is_content(line))
+            .map(|line| indentation(line))
+        else {
+            return Vec::new();
+        };
+
+        let starts: Vec<usize> = (start..end)
+            .filter(|index| {
+                let line = &self.lines[*index];
+
+                is_content(line)
+                    && indentation(line) == indent
+                    && parse_key(line.trim_start()).is_some()
+            })
+            .collect();
+
+        starts
+            .iter()
+            .enumerate()
+            .map(|(position, line)| {
+                let mut entry_end = starts.get(position + 1).copied().unwrap_or(end);
+
+                while entry_end > line + 1 && !self.belongs_to(entry_end - 1, indent) {
+                    entry_end -= 1;
+                }
+
+                Entry {
+                    line: *line,
+                    indent,
+                    end: entry_end,
+                }
+            })
+            .collect()
+    }
+
+    fn belongs_to(&self, index: usize, indent: usize) -> bool {
+        let line = &self.lines[index];
+
+        if line.trim().is_empty() {
+            return false;
+        }
+
+        is_content(line) || indentation(line) > indent
+    }
+}
+
+impl std::fmt::Display for Document {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Strips document markers and surrounding empty lines from a YAML snippet, and indents it
+pub fn reindent(block: &str, indent: usize) -> Vec<String> {
+    let lines: Vec<&str> = block
+        .lines()
+        .filter(|line| !is_document_marker(line))
+        .collect();
+
+    let first = lines.iter().position(|line| !line.trim().is_empty());
+    let last = lines.iter().rposition(|line| !line.trim().is_empty());
+
+    let (Some(first), Some(last)) = (first, last) else {
+        return Vec::new();
+    };
+
+    let lines = &lines[first..=last];
+
+    let base = lines
+        .iter()
+        .find(|line| is_content(line))
+        .map(|line| indentation(line))
+        .unwrap_or_default();
+    let prefix = " ".repeat(indent);
+
+    lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                String::new()
+            } else if indentation(line) >= base {
+                format!("{prefix}{}", &line[base..])
+            } else {
+                format!("{prefix}{}", line.trim_start())
+            }
+        })
+        .collect()
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim();
+
+    !trimmed.is_empty() && !trimmed.starts_with('#') && !is_document_marker(line)
+}
+
+fn is_document_marker(line: &str) -> bool {
+    let trimmed = line.trim_end();
+
+    trimmed == "---" || trimmed == "..." || trimmed.starts_with("--- ")
+}
+
+/// Splits the anchor and tag off the start of an inline value, e.g. `&jobs {}` into `&jobs` and `{}`
+fn split_properties(value: &str) -> (&str, &str) {
+    let mut rest = value;
+
+    while rest.starts_with(['&', '!']) {
+        rest = match rest.find(char::is_whitespace) {
+            Some(end) => rest[end..].trim_start(),
+            None => "",
+        };
+    }
+
+    (value[..value.len() - rest.len()].trim_end(), rest)
+}
+
+/// Parses the key of a block mapping entry, returning the key and the offset of its value
+fn parse_key(line: &str) -> Option<(String, usize)> {
+    let (key, rest_offset) = match line.chars().next()? {
+        '"' | '\'' => {
+            let quote = line.chars().next()?;
+            let closing = line[1..].find(quote)? + 1;
+
+            (line[1..closing].to_string(), closing + 1)
+        }
+        '#' | '-' | '[' | '{' | '?' | '|' | '>' | '&' | '*' | '!' | '%' | '@' | '`' => {
+            if line.starts_with('-') && !line.starts_with("- ") && line != "-" {
+                plain_key(line)?
+            } else {
+                return None;
+            }
+        }
+        _ => plain_key(line)?,
+    };
+
+    let rest = &line[rest_offset..];
+    let colon = rest.len() - rest.trim_start().len();
+
+    if !rest[colon..].starts_with(':') {
+        return None;
+    }
+
+    let value = &rest[colon + 1..];
+    if !value.is_empty() && !value.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    Some((key, rest_offset + colon + 1))
+}
+
+fn plain_key(line: &str) -> Option<(String, usize)> {
+    let bytes = line.as_bytes();
+
+    for (index, byte) in bytes.iter().enumerate() {
+        match byte {
+            b'#' if index > 0 && bytes[index - 1] == b' ' => return None,
+            b':' if index + 1 == bytes.len() || matches!(bytes[index + 1], b' ' | b'\t') => {
+                return Some((line[..index].trim_end().to_string(), index));
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn strip_comment(value: &str) -> &str {
+    let mut quote = None;
+    let bytes = value.as_bytes();
+
+    for (index, byte) in bytes.iter().enumerate() {
+        match (quote, byte) {
+            (None, b'"' | b'\'') => quote = Some(*byte),
+            (Some(open), _) if open == *byte => quote = None,
+            (None, b'#') if index == 0 || matches!(bytes[index - 1], b' ' | b'\t') => {
+                return &value[..index];
+            }
+            _ => {}
+        }
+    }
+
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const DOCUMENT: &str = indoc!(
+        r#"
+        ---
+        # The name of the workflow
+        name: Workflow
+
+        "on":
+          push: # jobs: are not a key here
+
+        jobs:
+          first:
+            name: First
+            steps:
+              - run: |
+                  echo "jobs:"
+
+        # Trailing comment
+        env:
+          jobs: nested
+        "#
+    );
+
+    fn document() -> Document {
+        Document::parse("test", DOCUMENT).unwrap()
+    }
+
+    #[test]
+    fn parse_errors_on_invalid_yaml() {
+        let error = Document::parse("test", "jobs: [").unwrap_err();
+
+        assert!(error.to_string().contains("failed to parse 'test'"));
+    }
+
+    #[test]
+    fn parse_errors_on_sequence() {
+        let error = Document::parse("test", "- first\n- second\n").unwrap_err();
+
+        assert_eq!(
+            "failed to render workflow: 'test' must be a YAML mapping",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn entries() {
+        let document = document();
+
+        let keys: Vec<String> = document
+            .entries()
+            .iter()
+            .map(|entry| document.key(entry))
+            .collect();
+
+        assert_eq!(vec!["name", "on", "jobs", "env"], keys);
+    }
+
+    #[test]
+    fn children() {
+        let document = document();
+        let jobs = document.get("jobs").unwrap();
+
+        let keys: Vec<String> = document
+            .children(&jobs)
+            .iter()
+            .map(|entry| document.key(entry))
+            .collect();
+
+        assert_eq!(vec!["first"], keys);
+    }
+
+    #[test]
+    fn append_preserves_comments() {
+        let mut document = document();
+        let jobs = document.get("jobs").unwrap();
+
+        document.append(&jobs, "second:\n  name: Second\n");
+
+        assert_eq!(
+            indoc!(
+                r#"
+                ---
+                # The name of the workflow
+                name: Workflow
+
+                "on":
+                  push: # jobs: are not a key here
+
+                jobs:
+                  first:
+                    name: First
+                    steps:
+                      - run: |
+                          echo "jobs:"
+
+                  second:
+                    name: Second
+
+                # Trailing comment
+                env:
+                  jobs: nested
+                "#
+            ),
+            document.to_string()
+        );
+    }
+
+    #[test]
+    fn expand_flow_mapping() {
+        let mut document = Document::parse("test", "jobs: {} # no jobs\n").unwrap();
+        let jobs = document.get("jobs").unwrap();
+
+        let jobs = document
+            .expand(&jobs, &Value::Mapping(Default::default()))
+            .unwrap();
+        document.append(&jobs, "first:\n  name: First\n");
+
+        assert_eq!("jobs:\n  first:\n    name: First\n", document.to_string());
+    }
+
+    #[test]
+    fn expand_block_mapping_with_anchor() {
+        let yaml = "jobs: &jobs\n  first:\n    name: First\n";
+        let mut document = Document::parse("test", yaml).unwrap();
+        let jobs = document.get("jobs").unwrap();
+        let value: Value = serde_yaml::from_str("first:\n  name: First\n").unwrap();
+
+        let expanded = document.expand(&jobs, &value).unwrap();
+
+        assert_eq!(jobs, expanded);
+        assert_eq!(yaml, document.to_string());
+    }
+
+    #[test]
+    fn expand_flow_mapping_with_anchor_and_tag() {
+        let mut document = Document::parse("test", "jobs: &jobs !!map {}\n").unwrap();
+        let jobs = document.get("jobs").unwrap();
+
+        let jobs = document
+            .expand(&jobs, &Value::Mapping(Default::default()))
+            .unwrap();
+        document.append(&jobs, "first:\n  name: First\n");
+
+        assert_eq!(
+            "jobs: &jobs !!map\n  first:\n    name: First\n",
+            document.to_string()
+        );
+    }
+
+    #[test]
+    fn split_properties_of_inline_value() {
+        assert_eq!(("&jobs !!map", "{}"), split_properties("&jobs !!map {}"));
+        assert_eq!(("&jobs", ""), split_properties("&jobs"));
+        assert_eq!(("", "{}"), split_properties("{}"));
+    }
+
+    #[test]
+    fn items() {
+        let document = document();
+        let first = document.children(&document.get("jobs").unwrap())[0];
+        let steps = document.children(&first)[1];
+
+        assert_eq!(vec![11], document.items(&steps));
+    }
+
+    #[test]
+    fn set_replaces_existing_child() {
+        let mut document =
+            Document::parse("test", "test:\n  name: Test\n  needs:\n    - build\n").unwrap();
+        let test = document.get("test").unwrap();
+
+        document.set(&test, "needs", "[build, lint]");
+
+        assert_eq!(
+            "test:\n  name: Test\n  needs: [build, lint]\n",
+            document.to_string()
+        );
+    }
+
+    #[test]
+    fn set_inserts_child_after_name() {
+        let mut document =
+            Document::parse("test", "test:\n  name: Test\n  runs-on: ubuntu-latest\n").unwrap();
+        let test = document.get("test").unwrap();
+
+        document.set(&test, "needs", "build");
+
+        assert_eq!(
+            "test:\n  name: Test\n  needs: build\n  runs-on: ubuntu-latest\n",
+            document.to_string()
+        );
+    }
+
+    #[test]
+    fn rename_keeps_value_and_comment() {
+        let mut document = Document::parse("test", "test: # The tests\n  name: Test\n").unwrap();
+        let test = document.get("test").unwrap();
+
+        document.rename(&test, "test-2");
+
+        assert_eq!("test-2: # The tests\n  name: Test\n", document.to_string());
+    }
+
+    #[test]
+    fn remove_child_with_separator() {
+        let mut document = Document::parse(
+            "test",
+            "jobs:\n  first:\n    name: First\n\n  second:\n    name: Second\n",
+        )
+        .unwrap();
+        let jobs = document.get("jobs").unwrap();
+        let second = document.children(&jobs)[1];
+
+        document.remove(&second);
+
+        assert_eq!("jobs:\n  first:\n    name: First\n", document.to_string());
+    }
+
+    #[test]
+    fn remove_first_child() {
+        let mut document = Document::parse(
+            "test",
+            "jobs:\n  first:\n    name: First\n\n  second:\n    name: Second\n",
+        )
+        .unwrap();
+        let jobs = document.get("jobs").unwrap();
+        let first = document.children(&jobs)[0];
+
+        document.remove(&first);
+
+        assert_eq!("jobs:\n  second:\n    name: Second\n", document.to_string());
+    }
+
+    #[test]
+    fn set_block_replaces_existing_child() {
+        let mut document = Document::parse(
+            "test",
+            "test:\n  strategy:\n    fail-fast: false\n    matrix: {os: [ubuntu-latest]}\n",
+        )
+        .unwrap();
+        let test = document.get("test").unwrap();
+        let strategy = document.children(&test)[0];
+        let matrix: Value = serde_yaml::from_str("os: [ubuntu-latest, macos-latest]").unwrap();
+
+        document.set_block(&strategy, "matrix", &matrix).unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                test:
+                  strategy:
+                    fail-fast: false
+                    matrix:
+                      os:
+                      - ubuntu-latest
+                      - macos-latest
+                "#
+            ),
+            document.to_string()
+        );
+    }
+
+    #[test]
+    fn replace_item_with_sequence() {
+        let mut document = Document::parse(
+            "test",
+            "test:\n  steps:\n    - step: setup\n\n    - run: cargo test\n",
+        )
+        .unwrap();
+        let test = document.get("test").unwrap();
+        let steps = document.children(&test)[0];
+
+        document.replace_item(
+            &steps,
+            2,
+            "- uses: actions/checkout@v4\n- run: rustup show\n",
+        );
+
+        assert_eq!(
+            indoc!(
+                r#"
+                test:
+                  steps:
+                    - uses: actions/checkout@v4
+                    - run: rustup show
+
+                    - run: cargo test
+                "#
+            ),
+            document.to_string()
+        );
+    }
+
+    #[test]
+    fn parse_key_with_quotes() {
+        assert_eq!(Some(("on".into(), 5)), parse_key(r#""on": push"#));
+    }
+
+    #[test]
+    fn parse_key_ignores_sequence_items() {
+        assert_eq!(None, parse_key("- run: echo"));
+    }
+
+    #[test]
+    fn parse_key_ignores_urls() {
+        assert_eq!(None, parse_key("https://example.com"));
+    }
+
+    #[test]
+    fn reindent_strips_document_markers() {
+        let lines = reindent("---\nfirst:\n  name: First\n", 2);
+
+        assert_eq!(vec!["  first:", "    name: First"], lines);
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Document>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Document>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Document>();
+    }
+}
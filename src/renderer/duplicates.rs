@@ -0,0 +1,73 @@
+use std::fmt::{Display, Formatter};
+
+/// The strategy for jobs that are defined by more than one fragment
+///
+/// GitHub Actions rejects workflows that define a job twice, so rendering fails by default and
+/// names both fragments. Jobs can instead be renamed, or a fragment can override the jobs that
+/// the workflow template or an earlier fragment defines.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum DuplicateJobs {
+    /// Fails to render the workflow
+    #[default]
+    Error,
+    /// Appends a number to the id of the later job, e.g. `test-2`
+    Rename,
+    /// Replaces the earlier job with the later one
+    Override,
+}
+
+impl DuplicateJobs {
+    pub fn is_error(&self) -> bool {
+        *self == Self::Error
+    }
+}
+
+impl Display for DuplicateJobs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let strategy = match self {
+            Self::Error => "error",
+            Self::Rename => "rename",
+            Self::Override => "override",
+        };
+
+        write!(f, "{strategy}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize() {
+        let strategy: DuplicateJobs = serde_yaml::from_str("override").unwrap();
+
+        assert_eq!(DuplicateJobs::Override, strategy);
+    }
+
+    #[test]
+    fn trait_display() {
+        assert_eq!("rename", DuplicateJobs::Rename.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<DuplicateJobs>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<DuplicateJobs>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<DuplicateJobs>();
+    }
+}
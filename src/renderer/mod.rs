@@ -0,0 +1,1168 @@
+use std::fmt::{Display, Formatter};
+
+use serde_yaml::Value;
+
+use crate::error::Error;
+use crate::fragment::{job_reference_name, step_reference, step_references, Fragment};
+use crate::job::Job;
+use crate::matrix::Matrix;
+use crate::variable::Variables;
+use crate::workflow::Workflow;
+
+pub(crate) use self::document::{Document, Entry};
+pub use self::duplicates::DuplicateJobs;
+
+mod document;
+mod duplicates;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Renderer<'a> {
+    workflow: &'a Fragment,
+    jobs: &'a [Job],
+    variables: Option<&'a Variables>,
+    duplicates: DuplicateJobs,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(workflow: &'a Fragment, jobs: &'a [Job]) -> Self {
+        Self {
+            workflow,
+            jobs,
+            variables: None,
+            duplicates: DuplicateJobs::default(),
+        }
+    }
+
+    /// Sets the variables that are available to the workflow and all of its jobs
+    pub fn variables(mut self, variables: &'a Variables) -> Self {
+        self.variables = Some(variables);
+        self
+    }
+
+    /// Sets the strategy for jobs that are defined by more than one fragment
+    pub fn duplicates(mut self, duplicates: DuplicateJobs) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    pub fn render(&self) -> Result<Workflow, Error> {
+        let workflow_variables = self.variables.cloned().unwrap_or_default();
+
+        let workflow = self.interpolate(self.workflow, &workflow_variables)?;
+        let mut document = Document::parse(workflow.name(), workflow.template().get())?;
+
+        let mut workflow_jobs = match document.get("jobs") {
+            Some(jobs) => {
+                let value = self.parse(&workflow)?;
+                let workflow_jobs =
+                    self.ensure_jobs(&workflow, value.get("jobs").cloned().unwrap_or_default())?;
+
+                document.expand(&jobs, &workflow_jobs)?;
+                workflow_jobs
+            }
+            None => Value::Null,
+        };
+
+        let mut interpolated_jobs = Vec::with_capacity(self.jobs.len());
+        for job in self.jobs {
+            let variables = workflow_variables.merge(job.variables());
+            let fragment = self.interpolate(job.fragment(), &variables)?;
+
+            let value = self.ensure_jobs(&fragment, self.parse(&fragment)?)?;
+            let mut interpolated_job = (fragment, value);
+
+            if !step_references(&interpolated_job.1).is_empty() {
+                self.add_steps(&mut interpolated_job, job.steps(), &variables)?;
+            }
+
+            if let Some(id) = job.id() {
+                self.rename_job(&mut interpolated_job, id)?;
+            }
+            if !job.matrix().is_empty() {
+                self.add_matrix(&mut interpolated_job, job.matrix())?;
+            }
+
+            interpolated_jobs.push(interpolated_job);
+        }
+
+        self.resolve_duplicates(
+            &workflow,
+            &mut document,
+            &mut workflow_jobs,
+            &mut interpolated_jobs,
+        )?;
+
+        let mut jobs = document
+            .get("jobs")
+            .unwrap_or_else(|| document.push("jobs"));
+
+        for (fragment, value) in &interpolated_jobs {
+            // Fragments whose jobs have all been overridden are left out
+            if job_ids(value).is_empty() {
+                continue;
+            }
+
+            let needs = self.needs(fragment, &interpolated_jobs, &workflow_jobs)?;
+            let template = self.add_needs(fragment, value, &needs)?;
+
+            jobs = document.append(&jobs, &template);
+        }
+
+        let rendered = document.to_string();
+        serde_yaml::from_str::<Value>(&rendered).map_err(|error| {
+            Error::Render(format!("rendered workflow is not valid YAML: {error}"))
+        })?;
+
+        Ok(Workflow::new(rendered))
+    }
+
+    fn interpolate(&self, fragment: &Fragment, variables: &Variables) -> Result<Fragment, Error> {
+        let template =
+            fragment
+                .template()
+                .interpolate(fragment.name(), fragment.variables(), variables)?;
+
+        let mut interpolated = fragment.clone();
+        interpolated.set_template(template);
+
+        Ok(interpolated)
+    }
+
+    fn parse(&self, fragment: &Fragment) -> Result<Value, Error> {
+        serde_yaml::from_str(fragment.template().get()).map_err(|error| {
+            Error::Render(format!("failed to parse '{}': {error}", fragment.name()))
+        })
+    }
+
+    /// Finds jobs that are defined by more than one fragment, and resolves them with the strategy
+    /// for duplicate jobs
+    ///
+    /// Fragments are checked in the order in which they are rendered, starting with the jobs in
+    /// the workflow template, so that renamed or overriding jobs are always the later ones.
+    fn resolve_duplicates(
+        &self,
+        workflow: &Fragment,
+        document: &mut Document,
+        workflow_jobs: &mut Value,
+        jobs: &mut [(Fragment, Value)],
+    ) -> Result<(), Error> {
+        // The fragment that defines each job, with `None` for the workflow template
+        let mut owners: Vec<(String, Option<usize>)> = job_ids(workflow_jobs)
+            .into_iter()
+            .map(|id| (id, None))
+            .collect();
+
+        for index in 0..jobs.len() {
+            for id in job_ids(&jobs[index].1) {
+                let Some(position) = owners.iter().position(|(owner, _)| *owner == id) else {
+                    owners.push((id, Some(index)));
+                    continue;
+                };
+
+                match self.duplicates {
+                    DuplicateJobs::Error => {
+                        let first = match owners[position].1 {
+                            Some(owner) => jobs[owner].0.name(),
+                            None => workflow.name(),
+                        };
+
+                        return Err(Error::DuplicateJob(
+                            id,
+                            first.into(),
+                            jobs[index].0.name().into(),
+                        ));
+                    }
+                    DuplicateJobs::Rename => {
+                        let renamed = (2..)
+                            .map(|number| format!("{id}-{number}"))
+                            .find(|candidate| {
+                                !owners.iter().any(|(owner, _)| owner == candidate)
+                                    && !jobs.iter().any(|(_, value)| value.get(candidate).is_some())
+                            })
+                            .unwrap_or_default();
+
+                        self.edit_job(&mut jobs[index], &id, |document, entry| {
+                            document.rename(entry, &renamed)
+                        })?;
+                        owners.push((renamed, Some(index)));
+                    }
+                    DuplicateJobs::Override => {
+                        match owners[position].1 {
+                            Some(owner) => {
+                                self.edit_job(&mut jobs[owner], &id, Document::remove)?;
+                            }
+                            None => {
+                                if let Some(job) = document.get("jobs").and_then(|jobs| {
+                                    document
+                                        .children(&jobs)
+                                        .into_iter()
+                                        .find(|job| document.key(job) == id)
+                                }) {
+                                    document.remove(&job);
+                                }
+                                if let Some(workflow_jobs) = workflow_jobs.as_mapping_mut() {
+                                    workflow_jobs.remove(id.as_str());
+                                }
+                            }
+                        }
+
+                        owners[position].1 = Some(index);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Splices the steps that the jobs in a fragment reference with `- step: <name>` into their
+    /// `steps`, interpolating them with the variables of the job
+    fn add_steps(
+        &self,
+        job: &mut (Fragment, Value),
+        steps: &[Fragment],
+        variables: &Variables,
+    ) -> Result<(), Error> {
+        let (fragment, value) = job;
+        let mut document = Document::parse(fragment.name(), fragment.template().get())?;
+
+        // Entries are updated from the bottom up, so that the lines of earlier entries do not move
+        for entry in document.entries().into_iter().rev() {
+            let id = document.key(&entry);
+            let job_steps = match value.get(id.as_str()).and_then(|job| job.get("steps")) {
+                Some(Value::Sequence(job_steps)) => job_steps.clone(),
+                _ => continue,
+            };
+            if !job_steps.iter().any(|step| step_reference(step).is_some()) {
+                continue;
+            }
+
+            let Some(mut steps_entry) = document
+                .children(&entry)
+                .into_iter()
+                .find(|child| document.key(child) == "steps")
+            else {
+                continue;
+            };
+
+            let items = document.items(&steps_entry);
+            if items.len() != job_steps.len() {
+                return Err(Error::Render(format!(
+                    "steps of job '{id}' must be written as a block sequence to reference steps"
+                )));
+            }
+
+            for (item, step) in items.into_iter().zip(&job_steps).rev() {
+                let Some(reference) = step_reference(step) else {
+                    continue;
+                };
+
+                let step = steps
+                    .iter()
+                    .find(|step| step.name() == job_reference_name(reference))
+                    .ok_or_else(|| {
+                        Error::Render(format!(
+                            "job '{id}' references step '{reference}', which has not been loaded"
+                        ))
+                    })?;
+                let step = self.interpolate(step, variables)?;
+
+                if !matches!(self.parse(&step)?, Value::Sequence(_)) {
+                    return Err(Error::Render(format!(
+                        "step '{}' must be a list of steps",
+                        step.name()
+                    )));
+                }
+
+                steps_entry = document.replace_item(&steps_entry, item, step.template().get());
+            }
+        }
+
+        fragment.set_template(document.to_string().into());
+        *value = self.ensure_jobs(fragment, self.parse(fragment)?)?;
+
+        Ok(())
+    }
+
+    /// Replaces the key of the job in a fragment with the id that it has been given
+    fn rename_job(&self, job: &mut (Fragment, Value), id: &str) -> Result<(), Error> {
+        let ids = job_ids(&job.1);
+        let [key] = ids.as_slice() else {
+            return Err(Error::Render(format!(
+                "'{}' must define exactly one job to be added as '{id}'",
+                job.0.name()
+            )));
+        };
+
+        self.edit_job(job, key, |document, entry| document.rename(entry, id))
+    }
+
+    /// Merges a matrix into the `strategy.matrix` of every job in a fragment
+    fn add_matrix(&self, job: &mut (Fragment, Value), matrix: &Matrix) -> Result<(), Error> {
+        let (fragment, value) = job;
+        let mut document = Document::parse(fragment.name(), fragment.template().get())?;
+
+        // Entries are updated from the bottom up, so that the lines of earlier entries do not move
+        for entry in document.entries().into_iter().rev() {
+            let id = document.key(&entry);
+            let job = value.get(id.as_str()).cloned().unwrap_or_default();
+            let strategy = job.get("strategy").cloned().unwrap_or_default();
+
+            let merged = matrix.merge(&id, strategy.get("matrix"))?;
+
+            let entry = document.expand(&entry, &job)?;
+            let existing_strategy = document
+                .children(&entry)
+                .into_iter()
+                .find(|child| document.key(child) == "strategy");
+
+            match existing_strategy {
+                Some(existing_strategy) => {
+                    let existing_strategy = document.expand(&existing_strategy, &strategy)?;
+                    document.set_block(&existing_strategy, "matrix", &merged)?;
+                }
+                None => {
+                    let strategy =
+                        Value::Mapping([("matrix".into(), merged)].into_iter().collect());
+                    document.set_block(&entry, "strategy", &strategy)?;
+                }
+            }
+        }
+
+        fragment.set_template(document.to_string().into());
+        *value = self.ensure_jobs(fragment, self.parse(fragment)?)?;
+
+        Ok(())
+    }
+
+    /// Edits the entry of a job in a fragment, and parses the jobs of the fragment again
+    fn edit_job<F>(&self, job: &mut (Fragment, Value), id: &str, edit: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Document, &Entry),
+    {
+        let (fragment, value) = job;
+        let mut document = Document::parse(fragment.name(), fragment.template().get())?;
+
+        if let Some(entry) = document.get(id) {
+            edit(&mut document, &entry);
+        }
+
+        fragment.set_template(document.to_string().into());
+        *value = self.ensure_jobs(fragment, self.parse(fragment)?)?;
+
+        Ok(())
+    }
+
+    /// Returns the ids of the jobs that a job requires, which are either rendered with it or part
+    /// of the workflow template
+    fn needs(
+        &self,
+        job: &Fragment,
+        jobs: &[(Fragment, Value)],
+        workflow_jobs: &Value,
+    ) -> Result<Vec<String>, Error> {
+        let mut needs = Vec::new();
+
+        for requirement in job.metadata().requires() {
+            let name = job_reference_name(requirement);
+
+            let required_jobs: Vec<&Value> = jobs
+                .iter()
+                .filter(|(fragment, _)| fragment.name() == name)
+                .map(|(_, value)| value)
+                .collect();
+
+            if !required_jobs.is_empty() {
+                needs.extend(required_jobs.into_iter().flat_map(job_ids));
+            } else if workflow_jobs.get(name).is_some() {
+                needs.push(name.to_string());
+            } else {
+                return Err(Error::Render(format!(
+                    "job '{}' requires '{requirement}', which is not part of the workflow",
+                    job.name()
+                )));
+            }
+        }
+
+        Ok(needs)
+    }
+
+    /// Adds the required jobs to the `needs` of every job in a fragment, keeping existing needs
+    fn add_needs(
+        &self,
+        fragment: &Fragment,
+        value: &Value,
+        needs: &[String],
+    ) -> Result<String, Error> {
+        let template = fragment.template().get();
+        if needs.is_empty() {
+            return Ok(template.to_string());
+        }
+
+        let mut document = Document::parse(fragment.name(), template)?;
+
+        // Entries are updated from the bottom up, so that the lines of earlier entries do not move
+        for entry in document.entries().into_iter().rev() {
+            let id = document.key(&entry);
+            let job = value.get(id.as_str()).cloned().unwrap_or_default();
+
+            let mut job_needs: Vec<String> = match job.get("needs") {
+                Some(Value::String(need)) => vec![need.clone()],
+                Some(Value::Sequence(existing)) => existing
+                    .iter()
+                    .filter_map(|need| need.as_str().map(String::from))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let existing = job_needs.len();
+
+            for need in needs {
+                if !job_needs.contains(need) && *need != id {
+                    job_needs.push(need.clone());
+                }
+            }
+            if job_needs.len() == existing {
+                continue;
+            }
+
+            let entry = document.expand(&entry, &job)?;
+            let job_needs = match job_needs.as_slice() {
+                [need] => need.clone(),
+                _ => format!("[{}]", job_needs.join(", ")),
+            };
+
+            document.set(&entry, "needs", &job_needs);
+        }
+
+        Ok(document.to_string())
+    }
+
+    fn ensure_jobs(&self, fragment: &Fragment, jobs: Value) -> Result<Value, Error> {
+        match jobs {
+            Value::Mapping(_) | Value::Null => Ok(jobs),
+            _ => Err(Error::Render(format!(
+                "jobs in '{}' must be a mapping from job ids to jobs",
+                fragment.name()
+            ))),
+        }
+    }
+}
+
+/// Returns the ids of the jobs that are defined in a job fragment
+fn job_ids(jobs: &Value) -> Vec<String> {
+    jobs.as_mapping()
+        .map(|jobs| {
+            jobs.keys()
+                .filter_map(|id| id.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl<'a> Display for Renderer<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Renderer {{ workflow: {}, job_count: {} }}",
+            self.workflow,
+            self.jobs.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn fragment(template: &str) -> Fragment {
+        Fragment::builder()
+            .name("test")
+            .template(template.into())
+            .build()
+    }
+
+    fn job(template: &str) -> Job {
+        fragment(template).into()
+    }
+
+    #[test]
+    fn render_without_jobs_section() {
+        let workflow = fragment(indoc!(
+            r#"
+            ---
+            name: Workflow
+            "#
+        ));
+
+        let jobs = vec![
+            job(indoc!(
+                r#"
+                first:
+                  name: First
+
+                  runs-on: ubuntu-latest
+                "#
+            )),
+            job(indoc!(
+                r#"
+                second:
+                  name: Second
+                "#
+            )),
+        ];
+
+        let rendered = Renderer::new(&workflow, &jobs).render().unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                ---
+                name: Workflow
+
+                jobs:
+                  first:
+                    name: First
+
+                    runs-on: ubuntu-latest
+
+                  second:
+                    name: Second
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_with_jobs_section() {
+        let workflow = fragment(indoc!(
+            r#"
+            ---
+            name: Workflow
+
+            jobs:
+              first:
+                name: First
+            "#
+        ));
+
+        let jobs = vec![job(indoc!(
+            r#"
+            second:
+              name: Second
+            "#
+        ))];
+
+        let rendered = Renderer::new(&workflow, &jobs).render().unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                ---
+                name: Workflow
+
+                jobs:
+                  first:
+                    name: First
+
+                  second:
+                    name: Second
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_ignores_jobs_in_comments_and_strings() {
+        let workflow = fragment(indoc!(
+            r#"
+            ---
+            # jobs: are added below
+            name: "jobs: workflow"
+
+            env:
+              jobs: nested
+            "#
+        ));
+
+        let jobs = vec![job(indoc!(
+            r#"
+            first:
+              name: First
+            "#
+        ))];
+
+        let rendered = Renderer::new(&workflow, &jobs).render().unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                ---
+                # jobs: are added below
+                name: "jobs: workflow"
+
+                env:
+                  jobs: nested
+
+                jobs:
+                  first:
+                    name: First
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_with_jobs_section_before_other_keys() {
+        let workflow = fragment(indoc!(
+            r#"
+            ---
+            jobs:
+              first:
+                name: First
+
+            # Environment variables
+            env:
+              RUST_LOG: debug
+            "#
+        ));
+
+        let jobs = vec![job(indoc!(
+            r#"
+            ---
+            # The second job
+            second:
+              name: Second
+            "#
+        ))];
+
+        let rendered = Renderer::new(&workflow, &jobs).render().unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                ---
+                jobs:
+                  first:
+                    name: First
+
+                  # The second job
+                  second:
+                    name: Second
+
+                # Environment variables
+                env:
+                  RUST_LOG: debug
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_with_empty_flow_mapping() {
+        let workflow = fragment(indoc!(
+            r#"
+            ---
+            name: Workflow
+
+            jobs: {}
+            "#
+        ));
+
+        let jobs = vec![job(indoc!(
+            r#"
+            first:
+              name: First
+            "#
+        ))];
+
+        let rendered = Renderer::new(&workflow, &jobs).render().unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                ---
+                name: Workflow
+
+                jobs:
+                  first:
+                    name: First
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_errors_on_invalid_job() {
+        let workflow = fragment("name: Workflow\n");
+        let jobs = vec![job("- first\n- second\n")];
+
+        let error = Renderer::new(&workflow, &jobs).render().unwrap_err();
+
+        assert!(matches!(error, Error::Render(_)));
+    }
+
+    #[test]
+    fn render_with_requirements() {
+        let workflow = fragment(indoc!(
+            r#"
+            ---
+            name: Workflow
+
+            jobs:
+              detect-changes:
+                runs-on: ubuntu-latest
+            "#
+        ));
+
+        let build = indoc!(
+            r#"
+            ---
+            requires: [detect-changes]
+            ---
+            build:
+              name: Build
+              runs-on: ubuntu-latest
+            "#
+        );
+        let test = indoc!(
+            r#"
+            ---
+            requires: [build]
+            ---
+            test:
+              name: Test
+              needs: detect-changes
+              runs-on: ubuntu-latest
+            "#
+        );
+
+        let jobs = vec![
+            Job::from(Fragment::parse("build", build).unwrap()),
+            Job::from(Fragment::parse("test", test).unwrap()),
+        ];
+
+        let rendered = Renderer::new(&workflow, &jobs).render().unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                ---
+                name: Workflow
+
+                jobs:
+                  detect-changes:
+                    runs-on: ubuntu-latest
+
+                  build:
+                    name: Build
+                    needs: detect-changes
+                    runs-on: ubuntu-latest
+
+                  test:
+                    name: Test
+                    needs: [detect-changes, build]
+                    runs-on: ubuntu-latest
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_errors_on_missing_requirement() {
+        let workflow = fragment("name: Workflow\n");
+        let jobs = vec![Job::from(
+            Fragment::parse("test", "---\nrequires: [build]\n---\ntest: {}\n").unwrap(),
+        )];
+
+        let error = Renderer::new(&workflow, &jobs).render().unwrap_err();
+
+        assert_eq!(
+            "failed to render workflow: job 'test' requires 'build', which is not part of the workflow",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn render_errors_on_duplicate_job() {
+        let workflow = Fragment::parse("rust", "name: Rust\njobs:\n  test: {}\n").unwrap();
+        let jobs = vec![Job::from(Fragment::parse("test", "test: {}\n").unwrap())];
+
+        let error = Renderer::new(&workflow, &jobs).render().unwrap_err();
+
+        assert_eq!(
+            "job 'test' is defined in both 'rust' and 'test'",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn render_renames_duplicate_jobs() {
+        let workflow = fragment("name: Workflow\n");
+        let jobs = vec![
+            Job::from(Fragment::parse("unit", "test:\n  name: Unit\n").unwrap()),
+            Job::from(Fragment::parse("integration", "test:\n  name: Integration\n").unwrap()),
+        ];
+
+        let rendered = Renderer::new(&workflow, &jobs)
+            .duplicates(DuplicateJobs::Rename)
+            .render()
+            .unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                name: Workflow
+
+                jobs:
+                  test:
+                    name: Unit
+
+                  test-2:
+                    name: Integration
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_overrides_duplicate_jobs() {
+        let workflow = fragment(indoc!(
+            r#"
+            name: Workflow
+
+            jobs:
+              lint:
+                name: Lint
+
+              test:
+                name: Default
+            "#
+        ));
+        let jobs = vec![
+            Job::from(Fragment::parse("unit", "test:\n  name: Unit\n").unwrap()),
+            Job::from(Fragment::parse("integration", "test:\n  name: Integration\n").unwrap()),
+        ];
+
+        let rendered = Renderer::new(&workflow, &jobs)
+            .duplicates(DuplicateJobs::Override)
+            .render()
+            .unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                name: Workflow
+
+                jobs:
+                  lint:
+                    name: Lint
+
+                  test:
+                    name: Integration
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_with_job_ids() {
+        let workflow = fragment("name: Workflow\n");
+        let test = Fragment::parse("test", "test:\n  runs-on: ubuntu-latest\n").unwrap();
+        let deploy = Fragment::parse("deploy", "---\nrequires: [test]\n---\ndeploy: {}\n").unwrap();
+        let jobs = vec![
+            Job::builder()
+                .fragment(test.clone())
+                .id(Some("test-core".into()))
+                .build(),
+            Job::builder()
+                .fragment(test)
+                .id(Some("test-cli".into()))
+                .build(),
+            Job::from(deploy),
+        ];
+
+        let rendered = Renderer::new(&workflow, &jobs).render().unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                name: Workflow
+
+                jobs:
+                  test-core:
+                    runs-on: ubuntu-latest
+
+                  test-cli:
+                    runs-on: ubuntu-latest
+
+                  deploy:
+                    needs: [test-core, test-cli]
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_errors_on_id_for_multiple_jobs() {
+        let workflow = fragment("name: Workflow\n");
+        let jobs = vec![Job::builder()
+            .fragment(Fragment::parse("test", "unit: {}\nintegration: {}\n").unwrap())
+            .id(Some("test-core".into()))
+            .build()];
+
+        let error = Renderer::new(&workflow, &jobs).render().unwrap_err();
+
+        assert_eq!(
+            "failed to render workflow: 'test' must define exactly one job to be added as 'test-core'",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn render_with_matrix() {
+        let workflow = fragment("name: Workflow\n");
+        let mut matrix = Matrix::new();
+        matrix.insert("toolchain", vec!["stable".into(), "1.70".into()]);
+
+        let jobs = vec![
+            Job::builder()
+                .fragment(fragment(indoc!(
+                    r#"
+                    test:
+                      name: Test
+                      strategy:
+                        fail-fast: false # Run all combinations
+                        matrix:
+                          os: [ubuntu-latest, macos-latest]
+                      runs-on: ${{ matrix.os }}
+                    "#
+                )))
+                .matrix(matrix.clone())
+                .build(),
+            Job::builder()
+                .fragment(fragment("lint:\n  name: Lint\n"))
+                .matrix(matrix)
+                .build(),
+        ];
+
+        let rendered = Renderer::new(&workflow, &jobs)
+            .duplicates(DuplicateJobs::Rename)
+            .render()
+            .unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                name: Workflow
+
+                jobs:
+                  test:
+                    name: Test
+                    strategy:
+                      fail-fast: false # Run all combinations
+                      matrix:
+                        os:
+                        - ubuntu-latest
+                        - macos-latest
+                        toolchain:
+                        - stable
+                        - '1.70'
+                    runs-on: ${{ matrix.os }}
+
+                  lint:
+                    name: Lint
+                    strategy:
+                      matrix:
+                        toolchain:
+                        - stable
+                        - '1.70'
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_errors_on_matrix_conflict() {
+        let workflow = fragment("name: Workflow\n");
+        let mut matrix = Matrix::new();
+        matrix.insert("os", vec!["windows-latest".into()]);
+
+        let jobs = vec![Job::builder()
+            .fragment(fragment(
+                "test:\n  strategy:\n    matrix:\n      os: [ubuntu-latest]\n",
+            ))
+            .matrix(matrix)
+            .build()];
+
+        let error = Renderer::new(&workflow, &jobs).render().unwrap_err();
+
+        assert_eq!(
+            "failed to render workflow: matrix of job 'test' already declares 'os'",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn render_with_steps() {
+        let workflow = fragment("name: Workflow\n");
+        let setup = Fragment::parse(
+            "setup",
+            indoc!(
+                r#"
+                ---
+                variables:
+                  toolchain: stable
+                ---
+                - uses: actions/checkout@v4
+                - run: rustup toolchain install {{ toolchain }}
+                "#
+            ),
+        )
+        .unwrap();
+
+        let jobs = vec![Job::builder()
+            .fragment(fragment(indoc!(
+                r#"
+                test:
+                  steps:
+                    - step: setup
+
+                    - run: cargo test
+                "#
+            )))
+            .variables(Variables::from_iter([("toolchain", "nightly")]))
+            .steps(vec![setup])
+            .build()];
+
+        let rendered = Renderer::new(&workflow, &jobs).render().unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                name: Workflow
+
+                jobs:
+                  test:
+                    steps:
+                      - uses: actions/checkout@v4
+                      - run: rustup toolchain install nightly
+
+                      - run: cargo test
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_errors_on_missing_step() {
+        let workflow = fragment("name: Workflow\n");
+        let jobs = vec![job("test:\n  steps:\n    - step: setup\n")];
+
+        let error = Renderer::new(&workflow, &jobs).render().unwrap_err();
+
+        assert_eq!(
+            "failed to render workflow: job 'test' references step 'setup', which has not been loaded",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn render_with_variables() {
+        let workflow = Fragment::parse(
+            "workflow",
+            indoc!(
+                r#"
+                ---
+                variables:
+                  name: Workflow
+                ---
+                name: {{ name }}
+                "#
+            ),
+        )
+        .unwrap();
+
+        let jobs = vec![Job::builder()
+            .fragment(fragment(indoc!(
+                r#"
+                    test:
+                      runs-on: {{ runner }}
+                      env:
+                        TOOLCHAIN: {{ toolchain }}
+                    "#
+            )))
+            .variables(Variables::from_iter([("toolchain", "nightly")]))
+            .build()];
+
+        let variables = Variables::from_iter([("runner", "macos-latest"), ("toolchain", "stable")]);
+
+        let rendered = Renderer::new(&workflow, &jobs)
+            .variables(&variables)
+            .render()
+            .unwrap();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                ---
+                name: Workflow
+
+                jobs:
+                  test:
+                    runs-on: macos-latest
+                    env:
+                      TOOLCHAIN: nightly
+                "#
+            ),
+            rendered.get()
+        );
+    }
+
+    #[test]
+    fn render_errors_on_missing_variable() {
+        let workflow = fragment("name: Workflow\n");
+        let jobs = vec![Fragment::parse(
+            "test",
+            "---\nvariables:\n  runner:\n---\ntest:\n  runs-on: {{ runner }}\n",
+        )
+        .unwrap()
+        .into()];
+
+        let error = Renderer::new(&workflow, &jobs).render().unwrap_err();
+
+        assert!(
+            matches!(error, Error::MissingVariable(fragment, variable) if fragment == "test" && variable == "runner")
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Renderer>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Renderer>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Renderer>();
+    }
+}
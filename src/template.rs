@@ -1,5 +1,10 @@
 use std::fmt::{Display, Formatter};
 
+use serde_yaml::Value;
+
+use crate::error::Error;
+use crate::variable::{Variable, Variables};
+
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct Template(String);
 
@@ -11,6 +16,64 @@ impl Template {
     pub fn get(&self) -> &str {
         &self.0
     }
+
+    /// Replaces all `{{ variable }}` placeholders in the template
+    ///
+    /// Values are looked up in the provided variables first, and then fall back to the default
+    /// value of the declared variable. Expressions for GitHub Actions (`${{ ... }}`) and
+    /// placeholders for variables that are neither declared nor provided are left untouched.
+    ///
+    /// A placeholder that is the whole value of a key or sequence item is replaced with a YAML
+    /// scalar, which is quoted if the value would otherwise be read as something else, e.g. `1.70`
+    /// as the number `1.7`. Placeholders inside a longer string are replaced verbatim.
+    pub fn interpolate(
+        &self,
+        fragment: &str,
+        declared: &[Variable],
+        values: &Variables,
+    ) -> Result<Template, Error> {
+        let mut interpolated = String::with_capacity(self.0.len());
+        let mut rest = self.get();
+
+        while let Some(start) = rest.find("{{") {
+            let (before, after) = rest.split_at(start);
+            interpolated.push_str(before);
+
+            let Some(end) = after.find("}}") else {
+                rest = after;
+                break;
+            };
+
+            let placeholder = &after[..end + 2];
+            let name = after[2..end].trim();
+
+            let variable = declared.iter().find(|variable| variable.name() == name);
+            let value = values
+                .get(name)
+                .or_else(|| variable.and_then(Variable::default));
+
+            let remainder = &after[end + 2..];
+            match value {
+                _ if before.ends_with('$') || !is_variable_name(name) => {
+                    interpolated.push_str(placeholder)
+                }
+                Some(value) if is_scalar(&interpolated, remainder) => {
+                    interpolated.push_str(&scalar(value))
+                }
+                Some(value) => interpolated.push_str(value),
+                None if variable.is_some() => {
+                    return Err(Error::MissingVariable(fragment.into(), name.into()))
+                }
+                None => interpolated.push_str(placeholder),
+            }
+
+            rest = remainder;
+        }
+
+        interpolated.push_str(rest);
+
+        Ok(Template(interpolated))
+    }
 }
 
 impl Display for Template {
@@ -31,6 +94,47 @@ impl From<String> for Template {
     }
 }
 
+/// Checks if a placeholder is the whole value of a key or sequence item, e.g. `key: {{ value }}`
+fn is_scalar(before: &str, after: &str) -> bool {
+    let line = before.rsplit('\n').next().unwrap_or_default();
+    let indicator = line.trim_end();
+    let rest = after.split('\n').next().unwrap_or_default().trim();
+
+    let follows_indicator =
+        indicator.len() < line.len() && (indicator.ends_with(':') || indicator.trim_start() == "-");
+
+    follows_indicator && (rest.is_empty() || rest.starts_with('#'))
+}
+
+/// Writes a value as a plain YAML scalar if it reads back as the same value, and quotes it otherwise
+fn scalar(value: &str) -> String {
+    let is_plain = match serde_yaml::from_str::<Value>(value) {
+        Ok(Value::String(string)) => string == value,
+        Ok(Value::Number(number)) => number.to_string() == value,
+        Ok(Value::Bool(bool)) => bool.to_string() == value,
+        _ => false,
+    };
+
+    if is_plain && !value.contains('\n') {
+        return value.into();
+    }
+
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+
+    format!("\"{escaped}\"")
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '_' || char == '-')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,6 +146,108 @@ mod tests {
         assert_eq!("{{foo}}", template.get());
     }
 
+    #[test]
+    fn interpolate_with_value() {
+        let template = Template::new("toolchain: {{ toolchain }}");
+        let values = Variables::from_iter([("toolchain", "nightly")]);
+
+        let interpolated = template.interpolate("test", &[], &values).unwrap();
+
+        assert_eq!("toolchain: nightly", interpolated.get());
+    }
+
+    #[test]
+    fn interpolate_with_default() {
+        let template = Template::new("runs-on: {{runner}}");
+        let declared = vec![Variable::builder()
+            .name("runner")
+            .default("ubuntu-latest")
+            .build()];
+
+        let interpolated = template
+            .interpolate("test", &declared, &Variables::new())
+            .unwrap();
+
+        assert_eq!("runs-on: ubuntu-latest", interpolated.get());
+    }
+
+    #[test]
+    fn interpolate_ignores_github_expressions() {
+        let template = Template::new("os: ${{ matrix.os }} ${{ runner }}");
+
+        let interpolated = template
+            .interpolate("test", &[], &Variables::new())
+            .unwrap();
+
+        assert_eq!("os: ${{ matrix.os }} ${{ runner }}", interpolated.get());
+    }
+
+    #[test]
+    fn interpolate_quotes_values_that_are_not_strings() {
+        let template = Template::new("toolchain: {{ toolchain }}\nname: {{ name }}\n");
+        let values = Variables::from_iter([("toolchain", "1.70"), ("name", "a: b")]);
+
+        let interpolated = template.interpolate("test", &[], &values).unwrap();
+
+        assert_eq!("toolchain: \"1.70\"\nname: \"a: b\"\n", interpolated.get());
+    }
+
+    #[test]
+    fn interpolate_quotes_sequence_items() {
+        let template = Template::new("toolchains:\n  - {{ toolchain }} # MSRV\n");
+        let values = Variables::from_iter([("toolchain", "1.70")]);
+
+        let interpolated = template.interpolate("test", &[], &values).unwrap();
+
+        assert_eq!("toolchains:\n  - \"1.70\" # MSRV\n", interpolated.get());
+    }
+
+    #[test]
+    fn interpolate_keeps_numbers_and_booleans_plain() {
+        let template = Template::new("timeout-minutes: {{ timeout }}\nfail-fast: {{ fail-fast }}");
+        let values = Variables::from_iter([("timeout", "30"), ("fail-fast", "false")]);
+
+        let interpolated = template.interpolate("test", &[], &values).unwrap();
+
+        assert_eq!("timeout-minutes: 30\nfail-fast: false", interpolated.get());
+    }
+
+    #[test]
+    fn interpolate_inside_string_verbatim() {
+        let template = Template::new("run: cargo +{{ toolchain }} test");
+        let values = Variables::from_iter([("toolchain", "1.70")]);
+
+        let interpolated = template.interpolate("test", &[], &values).unwrap();
+
+        assert_eq!("run: cargo +1.70 test", interpolated.get());
+    }
+
+    #[test]
+    fn interpolate_ignores_undeclared_placeholders() {
+        let template = Template::new("run: echo '{{ name }}' | envsubst");
+
+        let interpolated = template
+            .interpolate("test", &[], &Variables::new())
+            .unwrap();
+
+        assert_eq!("run: echo '{{ name }}' | envsubst", interpolated.get());
+    }
+
+    #[test]
+    fn interpolate_errors_on_missing_variable() {
+        let template = Template::new("working-directory: {{ directory }}");
+        let declared = vec![Variable::builder().name("directory").build()];
+
+        let error = template
+            .interpolate("test", &declared, &Variables::new())
+            .unwrap_err();
+
+        assert_eq!(
+            "missing value for variable 'directory' in fragment 'test'",
+            error.to_string()
+        );
+    }
+
     #[test]
     fn trait_display() {
         let template = Template::new("{{foo}}");
@@ -0,0 +1,276 @@
+use serde_yaml::Value;
+
+use crate::error::Error;
+use crate::renderer::Document;
+
+use self::schema::{Schema, Segment};
+
+pub use self::violation::{Severity, Violation};
+
+mod schema;
+mod violation;
+
+/// Validates a workflow against the schema for GitHub Actions workflows
+///
+/// All violations are reported at once, each with the path to the invalid value and the line in
+/// the source where it is defined. The workflow is only rejected if any of them is an error, and
+/// the warnings are returned otherwise.
+pub fn validate(name: &str, source: &str) -> Result<Vec<Violation>, Error> {
+    let workflow: Value = serde_yaml::from_str(source).map_err(|error| {
+        let line = error.location().map(|location| location.line());
+        Error::Validation(
+            name.into(),
+            vec![Violation::new(line, String::new(), error.to_string())],
+        )
+    })?;
+
+    let violations = Schema::check(&workflow);
+    if violations.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let document = Document::parse(name, source).ok();
+    let (warnings, errors): (Vec<Violation>, Vec<Violation>) = violations
+        .into_iter()
+        .map(|(path, message, severity)| {
+            let line = document
+                .as_ref()
+                .and_then(|document| locate(document, &path));
+            Violation::new(line, format_path(&path), message).with_severity(severity)
+        })
+        .partition(Violation::is_warning);
+
+    if errors.is_empty() {
+        Ok(warnings)
+    } else {
+        Err(Error::Validation(name.into(), errors))
+    }
+}
+
+/// Returns the line of the deepest part of a path that can be found in the document
+fn locate(document: &Document, path: &[Segment]) -> Option<usize> {
+    let mut line = None;
+    let mut parent = None;
+    let mut entries = document.entries();
+
+    for segment in path {
+        match segment {
+            Segment::Key(key) => {
+                let Some(entry) = entries
+                    .iter()
+                    .find(|entry| document.key(entry) == *key)
+                    .copied()
+                else {
+                    break;
+                };
+
+                line = Some(entry.line_number());
+                entries = document.children(&entry);
+                parent = Some(entry);
+            }
+            Segment::Index(index) => {
+                // Keys inside of sequence items are not located, so the item is the deepest part
+                if let Some(item) =
+                    parent.and_then(|parent| document.items(&parent).get(*index).copied())
+                {
+                    line = Some(item + 1);
+                }
+                break;
+            }
+        }
+    }
+
+    line
+}
+
+fn format_path(path: &[Segment]) -> String {
+    let mut formatted = String::new();
+
+    for segment in path {
+        match segment {
+            Segment::Key(key) if formatted.is_empty() => formatted.push_str(key),
+            Segment::Key(key) => formatted.push_str(&format!(".{key}")),
+            Segment::Index(index) => formatted.push_str(&format!("[{index}]")),
+        }
+    }
+
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const WORKFLOW: &str = indoc!(
+        r#"
+        ---
+        name: Rust
+
+        "on":
+          push:
+            branches:
+              - main
+          schedule:
+            - cron: "0 0 * * 0"
+
+        jobs:
+          build:
+            name: Build
+            runs-on: ubuntu-latest
+            steps:
+              - uses: actions/checkout@v4
+              - run: cargo build
+
+          test:
+            needs: build
+            runs-on: [self-hosted, linux]
+            timeout-minutes: ${{ inputs.timeout }}
+            strategy:
+              matrix:
+                toolchain: [stable, beta]
+            steps:
+              - run: cargo test
+
+          release:
+            needs: [build, test]
+            uses: ./.github/workflows/release.yml
+            secrets: inherit
+        "#
+    );
+
+    fn violations(source: &str) -> Vec<String> {
+        match validate("workflow", source) {
+            Ok(warnings) => warnings.iter().map(Violation::to_string).collect(),
+            Err(Error::Validation(_, violations)) => {
+                violations.iter().map(Violation::to_string).collect()
+            }
+            Err(error) => panic!("unexpected error: {error}"),
+        }
+    }
+
+    #[test]
+    fn validate_valid_workflow() {
+        assert!(validate("workflow", WORKFLOW).unwrap().is_empty());
+    }
+
+    #[test]
+    fn validate_missing_keys() {
+        let source = indoc!(
+            r#"
+            name: Rust
+            jobs:
+              test:
+                steps:
+                  - run: cargo test
+            "#
+        );
+
+        assert_eq!(
+            vec![
+                "missing required key 'on'",
+                "line 3: jobs.test: missing required key 'runs-on'"
+            ],
+            violations(source)
+        );
+    }
+
+    #[test]
+    fn validate_recent_keys_and_events() {
+        let source = indoc!(
+            r#"
+            "on": image_version
+            jobs:
+              build:
+                runs-on: ubuntu-latest
+                snapshot: image
+                steps:
+                  - run: make
+            "#
+        );
+
+        assert_eq!(
+            Vec::<Violation>::new(),
+            validate("workflow", source).unwrap()
+        );
+    }
+
+    #[test]
+    fn validate_unknown_keys_and_events_are_warnings() {
+        let source = indoc!(
+            r#"
+            "on": [push, pull-request]
+            jobs:
+              test:
+                runs_on: ubuntu-latest
+                runs-on: ubuntu-latest
+                steps:
+                  - run: cargo test
+            "#
+        );
+
+        let warnings: Vec<String> = validate("workflow", source)
+            .unwrap()
+            .iter()
+            .map(Violation::to_string)
+            .collect();
+
+        assert_eq!(
+            vec![
+                "line 1: on[1]: unknown event 'pull-request'",
+                "line 4: jobs.test.runs_on: unknown key 'runs_on'"
+            ],
+            warnings
+        );
+    }
+
+    #[test]
+    fn validate_steps() {
+        let source = indoc!(
+            r#"
+            "on": push
+            jobs:
+              test:
+                runs-on: ubuntu-latest
+                steps:
+                  - uses: actions/checkout@v4
+                  - name: Run tests
+                    uses: actions-rs/cargo@v1
+                    run: cargo test
+            "#
+        );
+
+        assert_eq!(
+            vec!["line 7: jobs.test.steps[1]: a step cannot both 'uses' an action and 'run' a command"],
+            violations(source)
+        );
+    }
+
+    #[test]
+    fn validate_needs() {
+        let source = indoc!(
+            r#"
+            "on": push
+            jobs:
+              test:
+                needs: build
+                runs-on: ubuntu-latest
+                steps:
+                  - run: cargo test
+            "#
+        );
+
+        assert_eq!(
+            vec!["line 4: jobs.test.needs: job 'build' does not exist"],
+            violations(source)
+        );
+    }
+
+    #[test]
+    fn validate_invalid_yaml() {
+        let error = validate("workflow", "jobs: [\n").unwrap_err();
+
+        assert!(matches!(error, Error::Validation(_, violations) if violations.len() == 1));
+    }
+}
@@ -0,0 +1,432 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::validation::violation::Severity;
+
+/// The events that can trigger a workflow
+const EVENTS: &[&str] = &[
+    "branch_protection_rule",
+    "check_run",
+    "check_suite",
+    "create",
+    "delete",
+    "deployment",
+    "deployment_status",
+    "discussion",
+    "discussion_comment",
+    "fork",
+    "gollum",
+    "image_version",
+    "issue_comment",
+    "issues",
+    "label",
+    "merge_group",
+    "milestone",
+    "page_build",
+    "project",
+    "project_card",
+    "project_column",
+    "public",
+    "pull_request",
+    "pull_request_review",
+    "pull_request_review_comment",
+    "pull_request_target",
+    "push",
+    "registry_package",
+    "release",
+    "repository_dispatch",
+    "schedule",
+    "status",
+    "watch",
+    "workflow_call",
+    "workflow_dispatch",
+    "workflow_run",
+];
+
+const WORKFLOW_KEYS: &[&str] = &[
+    "name",
+    "run-name",
+    "on",
+    "permissions",
+    "env",
+    "defaults",
+    "concurrency",
+    "jobs",
+];
+
+const JOB_KEYS: &[&str] = &[
+    "name",
+    "needs",
+    "permissions",
+    "runs-on",
+    "environment",
+    "concurrency",
+    "outputs",
+    "env",
+    "defaults",
+    "if",
+    "steps",
+    "timeout-minutes",
+    "strategy",
+    "continue-on-error",
+    "container",
+    "services",
+    "snapshot",
+];
+
+const REUSABLE_WORKFLOW_JOB_KEYS: &[&str] = &[
+    "name",
+    "needs",
+    "permissions",
+    "if",
+    "uses",
+    "with",
+    "secrets",
+    "strategy",
+    "concurrency",
+];
+
+const STEP_KEYS: &[&str] = &[
+    "id",
+    "if",
+    "name",
+    "uses",
+    "run",
+    "working-directory",
+    "shell",
+    "with",
+    "env",
+    "continue-on-error",
+    "timeout-minutes",
+];
+
+const STRATEGY_KEYS: &[&str] = &["matrix", "fail-fast", "max-parallel"];
+
+/// A key in a mapping or an index in a sequence
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Checks a workflow against the rules of the schema for GitHub Actions workflows
+///
+/// The rules follow the structure of the public workflow schema on SchemaStore: the keys that are
+/// allowed at each level, the keys that are required, and the types of their values. Values that
+/// are only known when the workflow runs, e.g. `${{ inputs.timeout }}`, are accepted anywhere a
+/// scalar is expected.
+///
+/// Unknown keys and events are only warnings, since GitHub Actions keeps adding new ones.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Schema {
+    path: Vec<Segment>,
+    violations: Vec<(Vec<Segment>, String, Severity)>,
+}
+
+impl Schema {
+    /// Returns the paths to the invalid values in a workflow, what is wrong with them, and how
+    /// severe it is
+    pub fn check(workflow: &Value) -> Vec<(Vec<Segment>, String, Severity)> {
+        let mut schema = Self::default();
+        schema.workflow(workflow);
+
+        schema.violations
+    }
+
+    fn workflow(&mut self, workflow: &Value) {
+        let Some(workflow) = self.mapping(workflow) else {
+            return;
+        };
+
+        self.keys(workflow, WORKFLOW_KEYS, &["on", "jobs"]);
+
+        self.at("on", workflow, |schema, on| schema.events(on));
+        self.at("env", workflow, |schema, env| schema.env(env));
+        self.at("jobs", workflow, |schema, jobs| schema.jobs(jobs));
+    }
+
+    fn events(&mut self, on: &Value) {
+        match on {
+            Value::String(event) => self.event(event),
+            Value::Sequence(events) => {
+                for (index, event) in events.iter().enumerate() {
+                    self.path.push(Segment::Index(index));
+                    match event.as_str() {
+                        Some(event) => self.event(event),
+                        None => self.violation("must be the name of an event"),
+                    }
+                    self.path.pop();
+                }
+            }
+            Value::Mapping(events) => {
+                for (event, configuration) in events {
+                    let Some(event) = event.as_str() else {
+                        self.violation("events must be strings");
+                        continue;
+                    };
+
+                    self.path.push(Segment::Key(event.into()));
+                    if EVENTS.contains(&event) {
+                        self.event_configuration(event, configuration);
+                    } else {
+                        self.warning(format!("unknown event '{event}'"));
+                    }
+                    self.path.pop();
+                }
+            }
+            _ => self.violation("must be an event, a list of events, or a mapping of events"),
+        }
+    }
+
+    fn event(&mut self, event: &str) {
+        if !EVENTS.contains(&event) {
+            self.warning(format!("unknown event '{event}'"));
+        }
+    }
+
+    fn event_configuration(&mut self, event: &str, configuration: &Value) {
+        if event != "schedule" {
+            if !matches!(configuration, Value::Null | Value::Mapping(_)) {
+                self.violation("must be a mapping");
+            }
+            return;
+        }
+
+        let Value::Sequence(schedules) = configuration else {
+            self.violation("must be a list of schedules");
+            return;
+        };
+
+        for (index, schedule) in schedules.iter().enumerate() {
+            self.path.push(Segment::Index(index));
+            if schedule.get("cron").and_then(Value::as_str).is_none() {
+                self.violation("missing required key 'cron'");
+            }
+            self.path.pop();
+        }
+    }
+
+    fn jobs(&mut self, jobs: &Value) {
+        let Some(jobs) = self.mapping(jobs) else {
+            return;
+        };
+
+        if jobs.is_empty() {
+            self.violation("must contain at least one job");
+        }
+
+        let ids: Vec<&str> = jobs.keys().filter_map(Value::as_str).collect();
+
+        for (id, job) in jobs {
+            let Some(id) = id.as_str() else {
+                self.violation("job ids must be strings");
+                continue;
+            };
+
+            self.path.push(Segment::Key(id.into()));
+            if !is_job_id(id) {
+                self.violation(
+                    "job ids must start with a letter or '_', and contain only alphanumeric \
+                     characters, '-', or '_'",
+                );
+            }
+            self.job(id, job, &ids);
+            self.path.pop();
+        }
+    }
+
+    fn job(&mut self, id: &str, job: &Value, ids: &[&str]) {
+        let Some(job) = self.mapping(job) else {
+            return;
+        };
+
+        if job.contains_key("uses") {
+            self.keys(job, REUSABLE_WORKFLOW_JOB_KEYS, &["uses"]);
+            self.at("uses", job, |schema, uses| schema.string(uses));
+        } else {
+            self.keys(job, JOB_KEYS, &["runs-on"]);
+            self.at("runs-on", job, |schema, runs_on| schema.runs_on(runs_on));
+            self.at("steps", job, |schema, steps| schema.steps(steps));
+        }
+
+        self.at("needs", job, |schema, needs| schema.needs(id, needs, ids));
+        self.at("env", job, |schema, env| schema.env(env));
+        self.at("strategy", job, |schema, strategy| {
+            schema.strategy(strategy)
+        });
+        self.at("timeout-minutes", job, |schema, timeout| {
+            schema.number(timeout)
+        });
+    }
+
+    fn runs_on(&mut self, runs_on: &Value) {
+        match runs_on {
+            Value::String(_) | Value::Mapping(_) => {}
+            Value::Sequence(labels) if labels.iter().all(Value::is_string) => {}
+            _ => self.violation("must be a runner label, a list of labels, or a runner group"),
+        }
+    }
+
+    fn needs(&mut self, id: &str, needs: &Value, ids: &[&str]) {
+        let needs: Vec<&str> = match needs {
+            Value::String(need) => vec![need],
+            Value::Sequence(needs) if needs.iter().all(Value::is_string) => {
+                needs.iter().filter_map(Value::as_str).collect()
+            }
+            _ => {
+                self.violation("must be a job id or a list of job ids");
+                return;
+            }
+        };
+
+        for need in needs {
+            if need == id {
+                self.violation("a job cannot need itself");
+            } else if !ids.contains(&need) {
+                self.violation(format!("job '{need}' does not exist"));
+            }
+        }
+    }
+
+    fn steps(&mut self, steps: &Value) {
+        let Value::Sequence(steps) = steps else {
+            self.violation("must be a list of steps");
+            return;
+        };
+
+        if steps.is_empty() {
+            self.violation("must contain at least one step");
+        }
+
+        for (index, step) in steps.iter().enumerate() {
+            self.path.push(Segment::Index(index));
+            self.step(step);
+            self.path.pop();
+        }
+    }
+
+    fn step(&mut self, step: &Value) {
+        let Some(step) = self.mapping(step) else {
+            return;
+        };
+
+        self.keys(step, STEP_KEYS, &[]);
+
+        match (step.contains_key("uses"), step.contains_key("run")) {
+            (true, true) => {
+                self.violation("a step cannot both 'uses' an action and 'run' a command")
+            }
+            (false, false) => {
+                self.violation("a step must either 'uses' an action or 'run' a command")
+            }
+            _ => {}
+        }
+
+        self.at("uses", step, |schema, uses| schema.string(uses));
+        self.at("run", step, |schema, run| schema.string(run));
+        self.at("with", step, |schema, with| {
+            schema.mapping(with);
+        });
+        self.at("env", step, |schema, env| schema.env(env));
+        self.at("timeout-minutes", step, |schema, timeout| {
+            schema.number(timeout)
+        });
+    }
+
+    fn strategy(&mut self, strategy: &Value) {
+        let Some(strategy) = self.mapping(strategy) else {
+            return;
+        };
+
+        self.keys(strategy, STRATEGY_KEYS, &[]);
+        self.at("matrix", strategy, |schema, matrix| {
+            if !matrix.is_mapping() && !is_expression(matrix) {
+                schema.violation("must be a mapping or an expression");
+            }
+        });
+    }
+
+    fn env(&mut self, env: &Value) {
+        if !env.is_mapping() && !is_expression(env) {
+            self.violation("must be a mapping of environment variables");
+        }
+    }
+
+    fn string(&mut self, value: &Value) {
+        if !value.is_string() {
+            self.violation("must be a string");
+        }
+    }
+
+    fn number(&mut self, value: &Value) {
+        if !value.is_number() && !is_expression(value) {
+            self.violation("must be a number");
+        }
+    }
+
+    /// Returns the value as a mapping, or records a violation if it is something else
+    fn mapping<'v>(&mut self, value: &'v Value) -> Option<&'v Mapping> {
+        let mapping = value.as_mapping();
+        if mapping.is_none() {
+            self.violation("must be a mapping");
+        }
+
+        mapping
+    }
+
+    /// Checks that a mapping only contains the allowed keys, and all of the required ones
+    fn keys(&mut self, mapping: &Mapping, allowed: &[&str], required: &[&str]) {
+        for key in required {
+            if !mapping.contains_key(*key) {
+                self.violation(format!("missing required key '{key}'"));
+            }
+        }
+
+        for key in mapping.keys() {
+            match key.as_str() {
+                Some(key) if allowed.contains(&key) => {}
+                Some(key) => {
+                    self.path.push(Segment::Key(key.into()));
+                    self.warning(format!("unknown key '{key}'"));
+                    self.path.pop();
+                }
+                None => self.violation("keys must be strings"),
+            }
+        }
+    }
+
+    /// Checks the value of a key in a mapping, if the mapping contains it
+    fn at(&mut self, key: &str, mapping: &Mapping, check: impl FnOnce(&mut Self, &Value)) {
+        if let Some(value) = mapping.get(key) {
+            self.path.push(Segment::Key(key.into()));
+            check(self, value);
+            self.path.pop();
+        }
+    }
+
+    fn violation(&mut self, message: impl Into<String>) {
+        self.violations
+            .push((self.path.clone(), message.into(), Severity::Error));
+    }
+
+    fn warning(&mut self, message: impl Into<String>) {
+        self.violations
+            .push((self.path.clone(), message.into(), Severity::Warning));
+    }
+}
+
+fn is_job_id(id: &str) -> bool {
+    let mut characters = id.chars();
+
+    characters
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && characters.all(|character| {
+            character.is_ascii_alphanumeric() || character == '-' || character == '_'
+        })
+}
+
+fn is_expression(value: &Value) -> bool {
+    value
+        .as_str()
+        .is_some_and(|value| value.trim_start().starts_with("${{"))
+}
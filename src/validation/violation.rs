@@ -0,0 +1,127 @@
+use std::fmt::{Display, Formatter};
+
+/// How severe a violation is
+///
+/// Errors are parts of a workflow that GitHub Actions rejects. Warnings are keys and events that
+/// FlowCrafter does not know, which might have been added to GitHub Actions after its release.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+/// A part of a workflow that does not match the schema for GitHub Actions workflows
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Violation {
+    line: Option<usize>,
+    path: String,
+    message: String,
+    severity: Severity,
+}
+
+impl Violation {
+    pub(crate) fn new(line: Option<usize>, path: String, message: String) -> Self {
+        Self {
+            line,
+            path,
+            message,
+            severity: Severity::Error,
+        }
+    }
+
+    pub(crate) fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Returns the line of the violation, starting at one, if it could be located in the file
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Returns the path to the invalid value, e.g. `jobs.test.steps[0]`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.severity == Severity::Warning
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}: ")?;
+        }
+
+        match self.path.is_empty() {
+            true => write!(f, "{}", self.message),
+            false => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trait_display() {
+        let violation = Violation::new(
+            Some(12),
+            "jobs.test".into(),
+            "missing required key 'runs-on'".into(),
+        );
+
+        assert_eq!(
+            "line 12: jobs.test: missing required key 'runs-on'",
+            violation.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_display_without_path() {
+        let violation = Violation::new(None, String::new(), "must be a mapping".into());
+
+        assert_eq!("must be a mapping", violation.to_string());
+    }
+
+    #[test]
+    fn with_severity() {
+        let violation = Violation::new(None, "on".into(), "unknown event 'new'".into())
+            .with_severity(Severity::Warning);
+
+        assert!(violation.is_warning());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Severity>();
+        assert_send::<Violation>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Severity>();
+        assert_sync::<Violation>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Severity>();
+        assert_unpin::<Violation>();
+    }
+}
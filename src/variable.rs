@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use serde_yaml::Value;
+use typed_builder::TypedBuilder;
+
+use crate::error::Error;
+
+/// A variable that is declared by a fragment
+///
+/// Variables without a default value are required, and must be provided by the configuration when
+/// a fragment is rendered.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, TypedBuilder)]
+pub struct Variable {
+    #[builder(setter(into))]
+    name: String,
+    #[builder(default, setter(into, strip_option))]
+    default: Option<String>,
+}
+
+impl Variable {
+    /// Parses a variable declaration from a fragment's front matter
+    ///
+    /// The declaration can either be a scalar that is used as the default value, or a mapping with
+    /// an optional `default` key. An empty declaration marks the variable as required.
+    pub(crate) fn parse(name: &str, declaration: &Value) -> Result<Self, Error> {
+        let default = match declaration {
+            Value::Null => None,
+            Value::Mapping(mapping) => match mapping.get("default") {
+                Some(value) => scalar(value),
+                None => None,
+            },
+            value => scalar(value),
+        };
+
+        if !matches!(declaration, Value::Null | Value::Mapping(_)) && default.is_none() {
+            return Err(Error::InvalidTemplate(format!(
+                "variable '{name}' must have a scalar default value"
+            )));
+        }
+
+        Ok(Self {
+            name: name.into(),
+            default,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn default(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.default.is_none()
+    }
+}
+
+impl Display for Variable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Values for variables that are provided when rendering a fragment
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Variables(BTreeMap<String, String>);
+
+impl Variables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.0.insert(name.into(), value.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns a copy of these variables that is overridden by the given variables
+    pub fn merge(&self, overrides: &Variables) -> Variables {
+        let mut merged = self.0.clone();
+        merged.extend(overrides.0.clone());
+
+        Self(merged)
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for Variables
+where
+    K: Into<String>,
+    V: Into<String>,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Variables {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let values = BTreeMap::<String, Value>::deserialize(deserializer)?;
+
+        values
+            .into_iter()
+            .map(|(name, value)| match scalar(&value) {
+                Some(value) => Ok((name, value)),
+                None => Err(serde::de::Error::custom(format!(
+                    "variable '{name}' must be a scalar value"
+                ))),
+            })
+            .collect()
+    }
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(value) => Some(value.to_string()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::String(value) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_with_scalar_default() {
+        let variable = Variable::parse("toolchain", &Value::from("stable")).unwrap();
+
+        assert_eq!(Some("stable"), variable.default());
+    }
+
+    #[test]
+    fn parse_with_mapping() {
+        let declaration: Value = serde_yaml::from_str("default: ubuntu-latest").unwrap();
+
+        let variable = Variable::parse("runner", &declaration).unwrap();
+
+        assert_eq!(Some("ubuntu-latest"), variable.default());
+    }
+
+    #[test]
+    fn parse_without_default() {
+        let variable = Variable::parse("directory", &Value::Null).unwrap();
+
+        assert!(variable.is_required());
+    }
+
+    #[test]
+    fn parse_errors_on_sequence() {
+        let declaration: Value = serde_yaml::from_str("[a, b]").unwrap();
+
+        let error = Variable::parse("list", &declaration).unwrap_err();
+
+        assert!(matches!(error, Error::InvalidTemplate(_)));
+    }
+
+    #[test]
+    fn merge_overrides_values() {
+        let workflow = Variables::from_iter([("toolchain", "stable"), ("runner", "ubuntu")]);
+        let job = Variables::from_iter([("toolchain", "nightly")]);
+
+        let merged = workflow.merge(&job);
+
+        assert_eq!(Some("nightly"), merged.get("toolchain"));
+        assert_eq!(Some("ubuntu"), merged.get("runner"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_with_scalars() {
+        let variables: Variables =
+            serde_yaml::from_str("toolchain: \"1.70\"\nverbose: true\njobs: 4").unwrap();
+
+        assert_eq!(Some("1.70"), variables.get("toolchain"));
+        assert_eq!(Some("true"), variables.get("verbose"));
+        assert_eq!(Some("4"), variables.get("jobs"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trait_deserialize_errors_on_mapping() {
+        let result = serde_yaml::from_str::<Variables>("nested:\n  key: value");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn trait_display() {
+        let variable = Variable::builder().name("toolchain").build();
+
+        assert_eq!("toolchain", variable.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Variable>();
+        assert_send::<Variables>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Variable>();
+        assert_sync::<Variables>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Variable>();
+        assert_unpin::<Variables>();
+    }
+}
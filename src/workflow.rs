@@ -1,5 +1,24 @@
 use std::fmt::{Display, Formatter};
 
+use indoc::indoc;
+use sha2::{Digest, Sha256};
+
+use crate::error::Error;
+use crate::validation::{validate, Violation};
+
+const HEADER: &str = indoc!(
+    r#"
+    # This file is managed by FlowCrafter. Changes that are made by hand are
+    # detected, and must be overwritten with --force when the workflow is updated.
+    "#
+);
+const CHECKSUM_PREFIX: &str = "# checksum: sha256:";
+
+/// A rendered workflow
+///
+/// When the workflow is written to a file, it is prefixed with a header that marks it as managed by
+/// FlowCrafter and records a checksum of its contents. The checksum is used to detect changes that
+/// have been made to the file by hand.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct Workflow(String);
 
@@ -11,11 +30,66 @@ impl Workflow {
     pub fn get(&self) -> &str {
         &self.0
     }
+
+    /// Returns the SHA-256 checksum of the workflow
+    pub fn checksum(&self) -> String {
+        checksum(&self.0)
+    }
+
+    /// Validates the workflow against the schema for GitHub Actions workflows, and returns the
+    /// warnings if it is valid
+    ///
+    /// The workflow is validated as it is written to a file, so that the line numbers of violations
+    /// match the file.
+    pub fn validate(&self, name: &str) -> Result<Vec<Violation>, Error> {
+        validate(name, &self.to_string())
+    }
+
+    /// Checks if the contents of a workflow file no longer match the checksum in its header
+    ///
+    /// Files without a checksum are not considered to be modified, since they have either not
+    /// been created by FlowCrafter or were created before checksums were recorded.
+    pub fn is_modified(contents: &str) -> bool {
+        match split_header(contents) {
+            Some((expected, body)) => checksum(body) != expected,
+            None => false,
+        }
+    }
+}
+
+/// Splits a workflow file into the checksum in its header and the body that follows it
+fn split_header(contents: &str) -> Option<(&str, &str)> {
+    let mut offset = 0;
+
+    for line in contents.split_inclusive('\n') {
+        offset += line.len();
+
+        if let Some(checksum) = line.trim_end().strip_prefix(CHECKSUM_PREFIX) {
+            return Some((checksum.trim(), &contents[offset..]));
+        }
+        if !line.starts_with('#') {
+            return None;
+        }
+    }
+
+    None
+}
+
+fn checksum(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
 }
 
 impl Display for Workflow {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        write!(
+            f,
+            "{HEADER}{CHECKSUM_PREFIX}{}\n{}",
+            self.checksum(),
+            self.0
+        )
     }
 }
 
@@ -44,11 +118,52 @@ mod tests {
         assert_eq!(CONTENT, workflow.get());
     }
 
+    #[test]
+    fn is_modified_with_unchanged_file() {
+        let workflow = Workflow::new("name: Rust\n");
+
+        assert!(!Workflow::is_modified(&workflow.to_string()));
+    }
+
+    #[test]
+    fn is_modified_with_changed_file() {
+        let workflow = Workflow::new("name: Rust\n");
+        let contents = workflow.to_string().replace("Rust", "Edited by hand");
+
+        assert!(Workflow::is_modified(&contents));
+    }
+
+    #[test]
+    fn is_modified_without_header() {
+        assert!(!Workflow::is_modified("name: Rust\n"));
+    }
+
+    #[test]
+    fn validate_reports_lines_of_file() {
+        let workflow = Workflow::new("name: Rust\njobs:\n  test:\n    steps: []\n");
+
+        let error = workflow.validate("rust.yml").unwrap_err();
+
+        assert_eq!(
+            indoc!(
+                r#"
+                rust.yml is not a valid workflow:
+                  missing required key 'on'
+                  line 6: jobs.test: missing required key 'runs-on'
+                  line 7: jobs.test.steps: must contain at least one step"#
+            ),
+            error.to_string()
+        );
+    }
+
     #[test]
     fn trait_display() {
         let workflow = Workflow::new(CONTENT);
 
-        assert_eq!(CONTENT, format!("{}", workflow));
+        assert_eq!(
+            format!("{HEADER}{CHECKSUM_PREFIX}{}\n{CONTENT}", checksum(CONTENT)),
+            format!("{}", workflow)
+        );
     }
 
     #[test]